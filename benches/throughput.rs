@@ -0,0 +1,49 @@
+//! Parsing throughput on `resources/qgames.log.txt` repeated up to 1 GB (or
+//! `LOG_PARSER_BENCH_BYTES`). Run with `cargo bench --bench throughput`.
+
+use log_parser::LogParser;
+use std::io::{self, BufReader, Read};
+use std::time::Instant;
+
+/// Repeats a log until `remaining` bytes have been read.
+struct RepeatedLog {
+    data: Vec<u8>,
+    position: usize,
+    remaining: u64,
+}
+
+impl Read for RepeatedLog {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.data.len() - self.position).min(self.remaining as usize);
+        buf[..len].copy_from_slice(&self.data[self.position..self.position + len]);
+        self.position = (self.position + len) % self.data.len();
+        self.remaining -= len as u64;
+        Ok(len)
+    }
+}
+
+fn main() {
+    let bytes = std::env::var("LOG_PARSER_BENCH_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1 << 30);
+    let data = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/resources/qgames.log.txt")).unwrap();
+    let reader = BufReader::with_capacity(1 << 16, RepeatedLog { data, position: 0, remaining: bytes });
+
+    let start = Instant::now();
+    let mut parser = LogParser::new();
+    let mut games = 0;
+    for game in parser.stream(reader) {
+        game.unwrap();
+        games += 1;
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+
+    println!(
+        "parsed {} MB ({} games) in {:.2}s: {:.1} MB/s",
+        bytes >> 20,
+        games,
+        elapsed,
+        bytes as f64 / elapsed / (1 << 20) as f64
+    );
+}
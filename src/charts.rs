@@ -0,0 +1,347 @@
+use crate::html::escape;
+use crate::{format_duration, Action, Game, OverallStats};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const PALETTE: [&str; 10] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd",
+    "#8c564b", "#e377c2", "#7f7f7f", "#bcbd22", "#17becf",
+];
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 320.0;
+const MARGIN: f64 = 40.0;
+const LEGEND_WIDTH: f64 = 160.0;
+
+/// Line chart of each player's cumulative frags over match time.
+///
+/// A kill adds one frag; a suicide or `<world>` death removes one, like the
+/// in-game score.
+pub fn frags_over_time(game: &Game) -> String {
+    // Segments of a game merged across a restart are plotted one after another
+    let times = game.elapsed_times().unwrap_or_else(|| vec![0; game.events.len()]);
+
+    let mut series: BTreeMap<String, Vec<(u32, i32)>> = BTreeMap::new();
+    for (event, &time) in game.events.iter().zip(&times) {
+        if let Action::Kill { player_name, victim_name, .. } = &event.action {
+            let (name, delta) = if player_name == "<world>" || player_name == victim_name {
+                (victim_name, -1)
+            } else {
+                (player_name, 1)
+            };
+            let points = series.entry(name.clone()).or_insert_with(|| vec![(0, 0)]);
+            let frags = points.last().map_or(0, |p| p.1) + delta;
+            points.push((time, frags));
+        }
+    }
+
+    let end = game.duration().unwrap_or(0).max(1);
+    let min = series.values().flatten().map(|p| p.1).min().unwrap_or(0).min(0);
+    let max = series.values().flatten().map(|p| p.1).max().unwrap_or(0).max(1);
+
+    let plot_width = WIDTH - LEGEND_WIDTH - 2.0 * MARGIN;
+    let plot_height = HEIGHT - 2.0 * MARGIN;
+    let x = |time: u32| MARGIN + time as f64 / end as f64 * plot_width;
+    let y = |frags: i32| MARGIN + (max - frags) as f64 / (max - min) as f64 * plot_height;
+
+    let mut svg = open_svg(WIDTH, HEIGHT, &format!("Game {}: frags over time", game.id));
+    let _ = writeln!(
+        svg,
+        "<line x1=\"{m}\" y1=\"{zero:.1}\" x2=\"{right:.1}\" y2=\"{zero:.1}\" stroke=\"#999\"/>\
+         <line x1=\"{m}\" y1=\"{m}\" x2=\"{m}\" y2=\"{bottom:.1}\" stroke=\"#999\"/>",
+        m = MARGIN,
+        zero = y(0),
+        right = MARGIN + plot_width,
+        bottom = MARGIN + plot_height,
+    );
+    let _ = writeln!(
+        svg,
+        "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\
+         <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\
+         <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+        MARGIN - 4.0, y(max) + 4.0, max,
+        MARGIN - 4.0, y(min) + 4.0, min,
+        MARGIN + plot_width, HEIGHT - MARGIN + 16.0, format_duration(end),
+    );
+
+    for (index, (name, points)) in series.iter().enumerate() {
+        let color = PALETTE[index % PALETTE.len()];
+        // Step line: frags stay flat until the next kill
+        let mut path = String::new();
+        let mut last_frags = 0;
+        for (time, frags) in points {
+            if path.is_empty() {
+                let _ = write!(path, "M{:.1},{:.1}", x(*time), y(*frags));
+            } else {
+                let _ = write!(path, " L{:.1},{:.1} L{:.1},{:.1}", x(*time), y(last_frags), x(*time), y(*frags));
+            }
+            last_frags = *frags;
+        }
+        let _ = write!(path, " L{:.1},{:.1}", x(end), y(last_frags));
+        let _ = writeln!(svg, "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>", path, color);
+        write_legend_entry(&mut svg, index, name, color);
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Horizontal bar chart of kills per means of death, most used first.
+pub fn kills_by_means(title: &str, kills_by_means: &HashMap<String, u32>) -> String {
+    let mut sorted_means: Vec<_> = kills_by_means.iter().collect();
+    sorted_means.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+    let bar_height = 20.0;
+    let label_width = 180.0;
+    let height = 2.0 * MARGIN + sorted_means.len() as f64 * (bar_height + 4.0);
+    let max = sorted_means.first().map_or(1, |m| *m.1).max(1);
+    let scale = (WIDTH - label_width - 2.0 * MARGIN) / max as f64;
+
+    let mut svg = open_svg(WIDTH, height, title);
+    for (index, (method, count)) in sorted_means.iter().enumerate() {
+        let top = MARGIN + index as f64 * (bar_height + 4.0);
+        let width = **count as f64 * scale;
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\
+             <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{}\" fill=\"{}\"/>\
+             <text x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+            MARGIN + label_width - 6.0, top + 15.0, escape(method),
+            MARGIN + label_width, top, width, bar_height, PALETTE[0],
+            MARGIN + label_width + width + 4.0, top + 15.0, count,
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Pie chart of the means of death a player killed with across `games`.
+///
+/// Returns `None` if the player has no kills.
+pub fn player_weapons(player: &str, games: &[Game]) -> Option<String> {
+    let mut weapons: HashMap<&str, u32> = HashMap::new();
+    for game in games {
+        for event in game.get_kills() {
+            if let Action::Kill { player_name, victim_name, method, .. } = &event.action {
+                if player_name == player && victim_name != player {
+                    *weapons.entry(method.as_str()).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+    if weapons.is_empty() {
+        return None;
+    }
+
+    let mut sorted_weapons: Vec<_> = weapons.into_iter().collect();
+    sorted_weapons.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let total: u32 = sorted_weapons.iter().map(|w| w.1).sum();
+
+    let radius = (HEIGHT - 2.0 * MARGIN) / 2.0;
+    let (cx, cy) = (MARGIN + radius, MARGIN + radius);
+
+    let mut svg = open_svg(WIDTH, HEIGHT, &format!("{}: weapons", player));
+    let mut angle = 0.0_f64;
+    for (index, (method, count)) in sorted_weapons.iter().enumerate() {
+        let color = PALETTE[index % PALETTE.len()];
+        let sweep = *count as f64 / total as f64 * std::f64::consts::TAU;
+        if sorted_weapons.len() == 1 {
+            let _ = writeln!(svg, "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"{}\"/>", cx, cy, radius, color);
+        } else {
+            let point = |a: f64| (cx + radius * a.sin(), cy - radius * a.cos());
+            let (x1, y1) = point(angle);
+            let (x2, y2) = point(angle + sweep);
+            let large_arc = if sweep > std::f64::consts::PI { 1 } else { 0 };
+            let _ = writeln!(
+                svg,
+                "<path d=\"M{:.1},{:.1} L{:.1},{:.1} A{:.1},{:.1} 0 {} 1 {:.1},{:.1} Z\" fill=\"{}\"/>",
+                cx, cy, x1, y1, radius, radius, large_arc, x2, y2, color,
+            );
+        }
+        angle += sweep;
+        write_legend_entry(&mut svg, index, &format!("{} ({})", method, count), color);
+    }
+
+    svg.push_str("</svg>\n");
+    Some(svg)
+}
+
+/// Writes every chart into `dir`, creating it if needed: the frags and kills
+/// by means of each game with kills, the overall kills by means and the
+/// weapons of each ranked player, one file per player.
+pub fn write_all(games: &[Game], overall: &OverallStats, dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    for game in games {
+        if !game.kills_by_means.is_empty() {
+            fs::write(dir.join(format!("game-{}-frags.svg", game.id)), frags_over_time(game))?;
+            let title = format!("Game {}: kills by means", game.id);
+            fs::write(dir.join(format!("game-{}-means.svg", game.id)), kills_by_means(&title, &game.kills_by_means))?;
+        }
+    }
+
+    fs::write(
+        dir.join("overall-means.svg"),
+        kills_by_means("Overall kills by means", &overall.kills_by_means),
+    )?;
+
+    // Names that only differ in case or punctuation get numbered files
+    let mut file_names = HashSet::new();
+    for player in overall.leaderboard() {
+        if let Some(svg) = player_weapons(&player.name, games) {
+            let slug: String = player
+                .name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+                .collect();
+            let mut file_name = slug.clone();
+            for n in 2.. {
+                if file_names.insert(file_name.clone()) {
+                    break;
+                }
+                file_name = format!("{}-{}", slug, n);
+            }
+            fs::write(dir.join(format!("player-{}-weapons.svg", file_name)), svg)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn open_svg(width: f64, height: f64, title: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+         font-family=\"sans-serif\" font-size=\"12\">\n\
+         <text x=\"{x}\" y=\"20\" font-size=\"14\" font-weight=\"bold\">{t}</text>\n",
+        w = width,
+        h = height,
+        x = MARGIN,
+        t = escape(title),
+    )
+}
+
+fn write_legend_entry(svg: &mut String, index: usize, label: &str, color: &str) {
+    let left = WIDTH - LEGEND_WIDTH;
+    let top = MARGIN + index as f64 * 18.0;
+    let _ = writeln!(
+        svg,
+        "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"12\" height=\"12\" fill=\"{}\"/><text x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+        left, top, color, left + 16.0, top + 10.0, escape(label),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogParser;
+
+    #[test]
+    fn test_frags_over_time() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test Server
+0:30 Kill: 2 2 3: Alice killed Bob by MOD_RAILGUN
+0:45 Kill: 1022 2 22: <world> killed Alice by MOD_FALLING
+1:00 ShutdownGame:
+";
+        let mut parser = LogParser::new();
+        parser.parse_reader(log.as_bytes()).unwrap();
+
+        let svg = frags_over_time(&parser.get_games()[0]);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<path ").count(), 1);
+        assert!(svg.contains(">Alice</text>"));
+    }
+
+    #[test]
+    fn test_frags_over_time_after_restart() {
+        let mut parser = LogParser::new();
+        parser.set_merge_restarts(true);
+        for line in [
+            "0:00 InitGame: \\sv_hostname\\Test\\mapname\\q3dm17",
+            "5:00 Kill: 2 3 7: Alice killed Bob by MOD_ROCKET",
+            "0:00 InitGame: \\sv_hostname\\Test\\mapname\\q3dm17",
+            "1:00 Kill: 3 2 7: Bob killed Alice by MOD_ROCKET",
+            "1:00 ShutdownGame:",
+        ] {
+            parser.push_line(line).unwrap();
+        }
+
+        // Bob's kill comes at 6:00, the end of the chart, not at 1:00
+        let svg = frags_over_time(&parser.get_games()[0]);
+        let bob = svg.lines().find(|line| line.contains("#ff7f0e")).unwrap();
+        assert!(bob.starts_with("<path d=\"M40.0,"));
+        assert!(bob.contains(" L440.0,"));
+        assert!(!bob.contains(" L106.7,"));
+    }
+
+    #[test]
+    fn test_kills_by_means_chart() {
+        let mut kills = HashMap::new();
+        kills.insert("MOD_RAILGUN".to_string(), 3);
+        kills.insert("MOD_SHOTGUN".to_string(), 1);
+
+        let svg = kills_by_means("Weapons", &kills);
+        assert_eq!(svg.matches("<rect ").count(), 2);
+        assert!(svg.find("MOD_RAILGUN").unwrap() < svg.find("MOD_SHOTGUN").unwrap());
+    }
+
+    #[test]
+    fn test_player_weapons() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test Server
+0:01 Kill: 2 2 3: Alice killed Bob by MOD_RAILGUN
+0:02 Kill: 2 2 3: Alice killed Bob by MOD_SHOTGUN
+0:03 Kill: 2 2 2: Alice killed Alice by MOD_ROCKET_SPLASH
+0:04 ShutdownGame:
+";
+        let mut parser = LogParser::new();
+        parser.parse_reader(log.as_bytes()).unwrap();
+
+        let svg = player_weapons("Alice", parser.get_games()).unwrap();
+        assert_eq!(svg.matches("<path ").count(), 2);
+        assert!(!svg.contains("MOD_ROCKET_SPLASH"));
+        assert!(player_weapons("Bob", parser.get_games()).is_none());
+    }
+
+    #[test]
+    fn test_write_all() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test
+0:05 Kill: 2 3 7: Mal killed mal by MOD_ROCKET
+0:06 Kill: 3 2 7: mal killed Mal by MOD_ROCKET
+0:07 Kill: 4 5 7: Dono da Bola killed Dono_da_Bola by MOD_ROCKET
+0:08 Kill: 5 4 7: Dono_da_Bola killed Dono da Bola by MOD_ROCKET
+0:10 ShutdownGame:
+";
+        let mut parser = LogParser::new();
+        parser.parse_reader(log.as_bytes()).unwrap();
+        let dir = std::env::temp_dir().join(format!("log_parser_charts_{}", std::process::id()));
+        write_all(parser.get_games(), parser.get_overall_stats(), &dir).unwrap();
+
+        let mut files: Vec<String> =
+            fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name().into_string().unwrap()).collect();
+        files.sort();
+        // Names that look alike get a file each instead of overwriting each other
+        assert_eq!(
+            files,
+            [
+                "game-1-frags.svg",
+                "game-1-means.svg",
+                "overall-means.svg",
+                "player-dono-da-bola-2-weapons.svg",
+                "player-dono-da-bola-weapons.svg",
+                "player-mal-2-weapons.svg",
+                "player-mal-weapons.svg",
+            ]
+        );
+        let mal = fs::read_to_string(dir.join("player-mal-2-weapons.svg")).unwrap();
+        assert!(mal.contains(">mal: weapons</text>"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
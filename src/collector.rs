@@ -0,0 +1,250 @@
+use crate::{ActionRef, EventRef};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+/// Counts keyed by name, e.g. kills per means of death.
+pub type Counts = HashMap<String, u32>;
+
+/// A statistic counted event by event within each game and then merged
+/// across games into the overall statistics.
+///
+/// `kills_by_means` and `killers` are collected this way. Register more with
+/// `LogParser::add_collector`; their counts end up in `Game::stats` and
+/// `OverallStats::stats` under the collector's name.
+pub trait Collector: Send + Sync {
+    /// Name the counts are stored and reported under.
+    fn name(&self) -> &str;
+
+    /// Counts one event of a game. The event borrows from its log line, so
+    /// counting shouldn't allocate beyond new keys, see `increment`.
+    fn collect(&self, event: &EventRef<'_>, counts: &mut Counts);
+
+    /// Adds the counts of a finished game to the totals. Sums them by default.
+    fn merge(&self, totals: &mut Counts, game: &Counts) {
+        for (key, count) in game {
+            *totals.entry(key.clone()).or_insert(0) += count;
+        }
+    }
+}
+
+impl fmt::Debug for dyn Collector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Collector({})", self.name())
+    }
+}
+
+impl<C: Collector + ?Sized> Collector for Arc<C> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+    fn collect(&self, event: &EventRef<'_>, counts: &mut Counts) {
+        (**self).collect(event, counts)
+    }
+    fn merge(&self, totals: &mut Counts, game: &Counts) {
+        (**self).merge(totals, game)
+    }
+}
+
+/// Adds one to the count of `key`, only allocating the first time it's seen.
+pub fn increment(counts: &mut Counts, key: &str) {
+    match counts.get_mut(key) {
+        Some(count) => *count += 1,
+        None => {
+            counts.insert(key.to_string(), 1);
+        }
+    }
+}
+
+/// Kills per means of death, including deaths by `<world>`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KillsByMeans;
+
+impl Collector for KillsByMeans {
+    fn name(&self) -> &str {
+        "kills-by-means"
+    }
+
+    fn collect(&self, event: &EventRef<'_>, counts: &mut Counts) {
+        if let ActionRef::Kill { method, .. } = event.action {
+            increment(counts, method);
+        }
+    }
+}
+
+/// Kills per player; `<world>` isn't a player and is left out.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Killers;
+
+impl Collector for Killers {
+    fn name(&self) -> &str {
+        "killers"
+    }
+
+    fn collect(&self, event: &EventRef<'_>, counts: &mut Counts) {
+        if let ActionRef::Kill { player_name, .. } = event.action {
+            if player_name != "<world>" {
+                increment(counts, player_name);
+            }
+        }
+    }
+}
+
+/// Deaths per player, whoever or whatever killed them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Victims;
+
+impl Collector for Victims {
+    fn name(&self) -> &str {
+        "victims"
+    }
+
+    fn collect(&self, event: &EventRef<'_>, counts: &mut Counts) {
+        if let ActionRef::Kill { victim_name, .. } = event.action {
+            increment(counts, victim_name);
+        }
+    }
+}
+
+/// Item pickups per item, e.g. `weapon_rocketlauncher`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Items;
+
+impl Collector for Items {
+    fn name(&self) -> &str {
+        "items"
+    }
+
+    fn collect(&self, event: &EventRef<'_>, counts: &mut Counts) {
+        if let ActionRef::Item { description, .. } = event.action {
+            increment(counts, description);
+        }
+    }
+}
+
+/// The collectors that can be added by name, besides the two always
+/// collected into `kills_by_means` and `killers`.
+pub const EXTRA_COLLECTORS: &[&str] = &["victims", "items"];
+
+/// Looks up one of `EXTRA_COLLECTORS` by name.
+pub fn by_name(name: &str) -> Result<Arc<dyn Collector>, String> {
+    match name {
+        "victims" => Ok(Arc::new(Victims)),
+        "items" => Ok(Arc::new(Items)),
+        _ => Err(format!("unknown collector `{}`, expected one of: {}", name, EXTRA_COLLECTORS.join(", "))),
+    }
+}
+
+/// Runs each collector on an event of `stats`' game.
+pub(crate) fn collect(collectors: &[Arc<dyn Collector>], stats: &mut BTreeMap<String, Counts>, event: &EventRef<'_>) {
+    for collector in collectors {
+        if !stats.contains_key(collector.name()) {
+            stats.insert(collector.name().to_string(), Counts::new());
+        }
+        collector.collect(event, stats.get_mut(collector.name()).unwrap());
+    }
+}
+
+/// Merges the counts of each collector from `game` into `totals`.
+pub(crate) fn merge(
+    collectors: &[Arc<dyn Collector>],
+    totals: &mut BTreeMap<String, Counts>,
+    game: &BTreeMap<String, Counts>,
+) {
+    for collector in collectors {
+        if let Some(counts) = game.get(collector.name()) {
+            collector.merge(totals.entry(collector.name().to_string()).or_default(), counts);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogParser;
+
+    const LOG: &str = "\
+0:00 InitGame: \\sv_hostname\\Test
+0:01 Item: 2 weapon_rocketlauncher
+0:02 Kill: 2 3 7: Alice killed Bob by MOD_ROCKET_SPLASH
+0:03 Kill: 1022 2 22: <world> killed Alice by MOD_TRIGGER_HURT
+0:04 ShutdownGame:
+0:00 InitGame: \\sv_hostname\\Test
+0:01 Item: 3 weapon_rocketlauncher
+0:02 Kill: 3 2 7: Bob killed Alice by MOD_ROCKET_SPLASH
+0:03 ShutdownGame:
+";
+
+    /// Keeps each player's most kills in a single game instead of a sum.
+    struct BestGame;
+
+    impl Collector for BestGame {
+        fn name(&self) -> &str {
+            "best-game"
+        }
+        fn collect(&self, event: &EventRef<'_>, counts: &mut Counts) {
+            Killers.collect(event, counts)
+        }
+        fn merge(&self, totals: &mut Counts, game: &Counts) {
+            for (player, kills) in game {
+                let best = totals.entry(player.clone()).or_insert(0);
+                *best = (*best).max(*kills);
+            }
+        }
+    }
+
+    fn counts(pairs: &[(&str, u32)]) -> Counts {
+        pairs.iter().map(|(key, count)| (key.to_string(), *count)).collect()
+    }
+
+    #[test]
+    fn test_extra_collectors() {
+        let mut parser = LogParser::new();
+        parser.add_collector(Victims);
+        parser.add_collector(by_name("items").unwrap());
+        parser.parse_reader(LOG.as_bytes()).unwrap();
+
+        let game = &parser.get_games()[0];
+        assert_eq!(game.stats["victims"], counts(&[("Alice", 1), ("Bob", 1)]));
+        assert_eq!(game.stats["items"], counts(&[("weapon_rocketlauncher", 1)]));
+
+        let overall = parser.get_overall_stats();
+        assert_eq!(overall.stats["victims"], counts(&[("Alice", 2), ("Bob", 1)]));
+        assert_eq!(overall.stats["items"], counts(&[("weapon_rocketlauncher", 2)]));
+        assert_eq!(overall.servers["Test"].stats, overall.stats);
+        assert!(by_name("headshots").is_err());
+    }
+
+    #[test]
+    fn test_custom_merge() {
+        let log = format!("{}{}", LOG, LOG.replace("Bob killed Alice", "Bob killed Carol"));
+        let mut parser = LogParser::new();
+        parser.add_collector(BestGame);
+        parser.parse_parallel(&log, 3).unwrap();
+
+        let overall = parser.get_overall_stats();
+        assert_eq!(overall.stats["best-game"], counts(&[("Alice", 1), ("Bob", 1)]));
+        assert_eq!(overall.killers, counts(&[("Alice", 2), ("Bob", 2)]));
+    }
+
+    #[test]
+    fn test_restart_counts_as_one_game() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test\\mapname\\q3dm17
+5:00 Kill: 2 3 7: Alice killed Bob by MOD_ROCKET
+0:00 InitGame: \\sv_hostname\\Test\\mapname\\q3dm17
+1:00 Kill: 2 3 7: Alice killed Bob by MOD_ROCKET
+1:30 ShutdownGame:
+";
+        for threads in [1, 2] {
+            let mut parser = LogParser::new();
+            parser.set_merge_restarts(true);
+            parser.add_collector(BestGame);
+            parser.parse_parallel(log, threads).unwrap();
+
+            // Both kills are in the same game, so they add up
+            assert_eq!(parser.get_games()[0].stats["best-game"], counts(&[("Alice", 2)]));
+            assert_eq!(parser.get_overall_stats().stats["best-game"], counts(&[("Alice", 2)]));
+        }
+    }
+}
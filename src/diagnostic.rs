@@ -0,0 +1,35 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Why a line couldn't be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Malformed {
+    /// 1-based column, in bytes, of the part of the line at fault
+    pub column: usize,
+    pub reason: &'static str,
+}
+
+/// A malformed log line that was skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// File the line was read from, if it came from a file
+    pub source: Option<PathBuf>,
+    /// 1-based line number within its input
+    pub line: usize,
+    /// 1-based column, in bytes, of the part of the line at fault
+    pub column: usize,
+    pub text: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            Some(path) => write!(f, "{}:{}:{}", path.display(), self.line, self.column)?,
+            None => write!(f, "line {}, column {}", self.line, self.column)?,
+        }
+        write!(f, ": {}: {}", self.reason, self.text)
+    }
+}
+
+impl std::error::Error for Diagnostic {}
@@ -0,0 +1,128 @@
+use crate::diagnostic::Diagnostic;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Error returned by the parsing APIs of `LogParser`.
+#[derive(Debug)]
+pub enum ParseError {
+    /// Opening, reading or decompressing the input failed
+    Io {
+        /// File being read, if the input is a file
+        path: Option<PathBuf>,
+        /// 1-based number of the line being read, if reading had begun
+        line: Option<usize>,
+        source: io::Error,
+    },
+    /// A malformed line, only an error in strict mode
+    Malformed(Diagnostic),
+}
+
+impl ParseError {
+    pub(crate) fn io(path: Option<&Path>, line: Option<usize>, source: io::Error) -> Self {
+        ParseError::Io {
+            path: path.map(Path::to_path_buf),
+            line,
+            source,
+        }
+    }
+
+    /// 1-based line number of the malformed line, or of the line a read
+    /// failed on.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ParseError::Io { line, .. } => *line,
+            ParseError::Malformed(diagnostic) => Some(diagnostic.line),
+        }
+    }
+
+    /// 1-based column of the malformed part of the line.
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            ParseError::Io { .. } => None,
+            ParseError::Malformed(diagnostic) => Some(diagnostic.column),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io { path, line, .. } => {
+                match path {
+                    Some(path) => write!(f, "failed to read {}", path.display())?,
+                    None => write!(f, "failed to read input")?,
+                }
+                match line {
+                    Some(line) => write!(f, " at line {}", line),
+                    None => Ok(()),
+                }
+            }
+            ParseError::Malformed(diagnostic) => diagnostic.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Io { source, .. } => Some(source),
+            ParseError::Malformed(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogParser;
+    use std::error::Error;
+
+    #[test]
+    fn test_io_error_keeps_path_and_source() {
+        let path = std::env::temp_dir().join("log_parser_error_missing.log");
+        let err = LogParser::new().parse_file(&path).unwrap_err();
+
+        let ParseError::Io { path: Some(failed), source, .. } = &err else {
+            panic!("expected an I/O error, got {:?}", err);
+        };
+        assert_eq!(failed, &path);
+        assert_eq!(source.kind(), io::ErrorKind::NotFound);
+        assert!(err.source().is_some());
+        assert_eq!(err.line(), None);
+    }
+
+    struct FailingReader;
+
+    impl io::Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::InvalidData, "corrupt deflate stream"))
+        }
+    }
+
+    #[test]
+    fn test_io_error_has_line() {
+        let log = || io::Read::chain(&b"0:00 InitGame: a\n0:01 ClientConnect: 2\n"[..], FailingReader);
+
+        let err = LogParser::new().parse_reader(io::BufReader::new(log())).unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(3), None));
+        assert_eq!(err.to_string(), "failed to read input at line 3");
+
+        let err = LogParser::new().parse_reader_parallel(log(), 2).unwrap_err();
+        assert_eq!(err.line(), Some(3));
+    }
+
+    #[test]
+    fn test_malformed_error_has_position() {
+        let mut parser = LogParser::new();
+        parser.set_strict(true);
+        let err = parser.parse_reader(&b"0:00 InitGame: a\n0:01 Kill: 1 x 3: a killed b by c\n"[..]).unwrap_err();
+
+        assert!(matches!(err, ParseError::Malformed(_)));
+        assert_eq!((err.line(), err.column()), (Some(2), Some(14)));
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 14: expected `<killer id> <victim id> <means id>:` in Kill: 0:01 Kill: 1 x 3: a killed b by c"
+        );
+    }
+}
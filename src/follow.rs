@@ -0,0 +1,206 @@
+use std::fs::{self, File, Metadata};
+use std::io::{self, BufRead, BufReader};
+use std::mem;
+use std::path::{Path, PathBuf};
+
+/// Follows a growing log file like `tail -F`.
+///
+/// Each `poll` returns the complete lines appended since the previous one,
+/// without their line endings and still undecoded, so the parser can repair
+/// and count those that aren't valid UTF-8 (see `LogParser::decode_line`).
+/// When the path is replaced by a new file (log rotation) the rest of the
+/// old file is read first and the new file is then read from the start;
+/// when the file shrinks (truncation) it is re-read from the start.
+/// A single `poll` never returns lines of both files; `reopened` tells when
+/// its lines start at the top of a file again.
+pub struct Follower {
+    path: PathBuf,
+    reader: Option<BufReader<File>>,
+    file_id: Option<FileId>,
+    position: u64,
+    partial: Vec<u8>,
+    reopened: bool,
+}
+
+impl Follower {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Follower {
+            path: path.as_ref().to_path_buf(),
+            reader: None,
+            file_id: None,
+            position: 0,
+            partial: Vec::new(),
+            reopened: false,
+        }
+    }
+
+    pub fn poll(&mut self) -> io::Result<Vec<Vec<u8>>> {
+        let mut lines = Vec::new();
+        self.reopened = false;
+
+        if self.reader.is_none() && !self.open()? {
+            return Ok(lines);
+        }
+        self.read_available(&mut lines)?;
+
+        match fs::metadata(&self.path) {
+            Ok(metadata) => {
+                let rotated = file_id(&metadata) != self.file_id;
+                if rotated || metadata.len() < self.position {
+                    // Rotated: keep the old file's unterminated last line
+                    if rotated && !self.partial.is_empty() {
+                        lines.push(mem::take(&mut self.partial));
+                    }
+                    // Start over with the next poll if this one has lines of the old file
+                    self.reader = None;
+                    if lines.is_empty() && self.open()? {
+                        self.read_available(&mut lines)?;
+                    }
+                }
+            }
+            // Moved away and not recreated yet
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        Ok(lines)
+    }
+
+    /// Whether the lines of the last `poll` start at the top of the file,
+    /// because it was opened, rotated or truncated, so line numbers restart.
+    pub fn reopened(&self) -> bool {
+        self.reopened
+    }
+
+    /// Opens the file at `path` from the start. Returns `false` if it does
+    /// not exist (yet).
+    fn open(&mut self) -> io::Result<bool> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        self.file_id = file_id(&file.metadata()?);
+        self.reader = Some(BufReader::new(file));
+        self.position = 0;
+        self.partial.clear();
+        self.reopened = true;
+        Ok(true)
+    }
+
+    fn read_available(&mut self, lines: &mut Vec<Vec<u8>>) -> io::Result<()> {
+        let Some(reader) = &mut self.reader else {
+            return Ok(());
+        };
+
+        loop {
+            let read = reader.read_until(b'\n', &mut self.partial)?;
+            if read == 0 {
+                return Ok(());
+            }
+            self.position += read as u64;
+
+            // A line without a newline is still being written
+            if let Some(line) = self.partial.strip_suffix(b"\n") {
+                lines.push(line.strip_suffix(b"\r").unwrap_or(line).to_vec());
+                self.partial.clear();
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+type FileId = (u64, u64);
+
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+// Without inode numbers only truncation can be detected
+#[cfg(not(unix))]
+type FileId = ();
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<FileId> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("log_parser_follow_{}_{}.log", name, std::process::id()))
+    }
+
+    fn poll(follower: &mut Follower) -> Vec<String> {
+        follower.poll().unwrap().into_iter().map(|line| String::from_utf8(line).unwrap()).collect()
+    }
+
+    fn append(path: &Path, text: &str) {
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_follow_appended_and_partial_lines() {
+        let path = temp_path("append");
+        let _ = fs::remove_file(&path);
+        let mut follower = Follower::new(&path);
+
+        assert!(poll(&mut follower).is_empty());
+
+        append(&path, "0:00 InitGame: \\sv_hostname\\Test\n0:01 ClientConn");
+        assert_eq!(poll(&mut follower), vec!["0:00 InitGame: \\sv_hostname\\Test"]);
+
+        append(&path, "ect: 2\n");
+        assert_eq!(poll(&mut follower), vec!["0:01 ClientConnect: 2"]);
+
+        fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"0:02 Say: Caf\xe9\r\n").unwrap();
+        // Left for the parser to repair
+        assert_eq!(follower.poll().unwrap(), vec![b"0:02 Say: Caf\xe9".to_vec()]);
+        assert!(poll(&mut follower).is_empty());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_follow_truncation() {
+        let path = temp_path("truncate");
+        fs::write(&path, "0:00 InitGame: a\n0:01 ClientConnect: 2\n").unwrap();
+        let mut follower = Follower::new(&path);
+        assert_eq!(poll(&mut follower).len(), 2);
+
+        fs::write(&path, "0:00 InitGame: b\n").unwrap();
+        assert_eq!(poll(&mut follower), vec!["0:00 InitGame: b"]);
+        assert!(follower.reopened());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_follow_rotation() {
+        let path = temp_path("rotate");
+        let rotated = temp_path("rotate_old");
+        fs::write(&path, "0:00 InitGame: a\n").unwrap();
+        let mut follower = Follower::new(&path);
+        assert_eq!(poll(&mut follower).len(), 1);
+        assert!(follower.reopened());
+
+        append(&path, "0:05 ShutdownGame:\n");
+        fs::rename(&path, &rotated).unwrap();
+        fs::write(&path, "0:00 InitGame: b\n").unwrap();
+        // The new file is only read once the old one's lines are returned
+        assert_eq!(poll(&mut follower), vec!["0:05 ShutdownGame:"]);
+        assert!(!follower.reopened());
+        assert_eq!(poll(&mut follower), vec!["0:00 InitGame: b"]);
+        assert!(follower.reopened());
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&rotated).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{Action, Game, GameEvent};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Callbacks that `LogParser` invokes for each event as it parses, in log
+/// order, so statistics can be gathered without walking `Game::events`.
+///
+/// Every method does nothing by default; implement the ones you need. For
+/// each event `on_event` is called first, then the callback for its action.
+/// A game's `on_game_end` comes after its last event and before the
+/// `InitGame` of the next game; lines outside any game aren't reported.
+/// Register handlers with
+/// `LogParser::add_handler`; share one through `Arc<Mutex<_>>` to read its
+/// results after parsing.
+#[allow(unused_variables)]
+pub trait EventHandler {
+    fn on_event(&mut self, event: &GameEvent) {}
+    fn on_init_game(&mut self, timestamp: &str, details: &str) {}
+    fn on_shutdown(&mut self, timestamp: &str) {}
+    fn on_connect(&mut self, timestamp: &str, player_id: u32) {}
+    fn on_userinfo_changed(&mut self, timestamp: &str, player_id: u32, info: &str) {}
+    fn on_begin(&mut self, timestamp: &str, player_id: u32) {}
+    fn on_item(&mut self, timestamp: &str, item_id: u32, description: &str) {}
+    /// A kill, by `<world>` for deaths by falling, lava and the like.
+    fn on_kill(&mut self, timestamp: &str, killer: &str, victim: &str, means: &str) {}
+    fn on_disconnect(&mut self, timestamp: &str, player_id: u32) {}
+    /// Any other event, e.g. `Exit`, `say` or `score`.
+    fn on_other(&mut self, timestamp: &str, action_name: &str, details: &str) {}
+    /// A game ended, by `ShutdownGame` or by being cut off.
+    fn on_game_end(&mut self, game: &Game) {}
+}
+
+impl fmt::Debug for dyn EventHandler + Send {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EventHandler")
+    }
+}
+
+impl<H: EventHandler + ?Sized> EventHandler for Arc<Mutex<H>> {
+    fn on_event(&mut self, event: &GameEvent) {
+        self.lock().unwrap().on_event(event)
+    }
+    fn on_init_game(&mut self, timestamp: &str, details: &str) {
+        self.lock().unwrap().on_init_game(timestamp, details)
+    }
+    fn on_shutdown(&mut self, timestamp: &str) {
+        self.lock().unwrap().on_shutdown(timestamp)
+    }
+    fn on_connect(&mut self, timestamp: &str, player_id: u32) {
+        self.lock().unwrap().on_connect(timestamp, player_id)
+    }
+    fn on_userinfo_changed(&mut self, timestamp: &str, player_id: u32, info: &str) {
+        self.lock().unwrap().on_userinfo_changed(timestamp, player_id, info)
+    }
+    fn on_begin(&mut self, timestamp: &str, player_id: u32) {
+        self.lock().unwrap().on_begin(timestamp, player_id)
+    }
+    fn on_item(&mut self, timestamp: &str, item_id: u32, description: &str) {
+        self.lock().unwrap().on_item(timestamp, item_id, description)
+    }
+    fn on_kill(&mut self, timestamp: &str, killer: &str, victim: &str, means: &str) {
+        self.lock().unwrap().on_kill(timestamp, killer, victim, means)
+    }
+    fn on_disconnect(&mut self, timestamp: &str, player_id: u32) {
+        self.lock().unwrap().on_disconnect(timestamp, player_id)
+    }
+    fn on_other(&mut self, timestamp: &str, action_name: &str, details: &str) {
+        self.lock().unwrap().on_other(timestamp, action_name, details)
+    }
+    fn on_game_end(&mut self, game: &Game) {
+        self.lock().unwrap().on_game_end(game)
+    }
+}
+
+/// Passes an event to each handler.
+pub(crate) fn notify(handlers: &mut [Box<dyn EventHandler + Send>], event: &GameEvent) {
+    for handler in handlers {
+        handler.on_event(event);
+        let timestamp = event.timestamp.as_str();
+        match &event.action {
+            Action::InitGame { details } => handler.on_init_game(timestamp, details),
+            Action::ShutdownGame => handler.on_shutdown(timestamp),
+            Action::ClientConnect { player_id } => handler.on_connect(timestamp, *player_id),
+            Action::ClientUserinfoChanged { player_id, info } => handler.on_userinfo_changed(timestamp, *player_id, info),
+            Action::ClientBegin { player_id } => handler.on_begin(timestamp, *player_id),
+            Action::Item { item_id, description } => handler.on_item(timestamp, *item_id, description),
+            Action::Kill { player_name, victim_name, method, .. } => {
+                handler.on_kill(timestamp, player_name, victim_name, method)
+            }
+            Action::ClientDisconnect { player_id } => handler.on_disconnect(timestamp, *player_id),
+            Action::Other { action_name, details } => handler.on_other(timestamp, action_name, details),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogParser;
+
+    /// Records every callback as a line of text.
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl EventHandler for Recorder {
+        fn on_init_game(&mut self, timestamp: &str, _details: &str) {
+            self.0.push(format!("{} init", timestamp));
+        }
+        fn on_kill(&mut self, timestamp: &str, killer: &str, victim: &str, means: &str) {
+            self.0.push(format!("{} {} killed {} by {}", timestamp, killer, victim, means));
+        }
+        fn on_shutdown(&mut self, timestamp: &str) {
+            self.0.push(format!("{} shutdown", timestamp));
+        }
+        fn on_game_end(&mut self, game: &Game) {
+            self.0.push(format!("end game {} ({})", game.id, game.status()));
+        }
+    }
+
+    #[test]
+    fn test_handler_callback_order() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test
+0:01 ClientConnect: 2
+0:02 Kill: 2 3 7: Alice killed Bob by MOD_ROCKET
+0:03 ShutdownGame:
+0:00 InitGame: \\sv_hostname\\Test
+0:04 Kill: 1022 2 22: <world> killed Alice by MOD_FALLING
+0:00 InitGame: \\sv_hostname\\Test
+";
+        let recorder = Arc::new(Mutex::new(Recorder::default()));
+        let mut parser = LogParser::new();
+        parser.add_handler(recorder.clone());
+        parser.parse_reader(log.as_bytes()).unwrap();
+
+        assert_eq!(
+            recorder.lock().unwrap().0,
+            vec![
+                "0:00 init",
+                "0:02 Alice killed Bob by MOD_ROCKET",
+                "0:03 shutdown",
+                "end game 1 (completed)",
+                "0:00 init",
+                "0:04 <world> killed Alice by MOD_FALLING",
+                "end game 2 (crash)",
+                "0:00 init",
+                "end game 3 (truncated)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_handlers_see_parallel_parse_in_log_order() {
+        let log = include_str!("../resources/qgames.log.txt");
+
+        for merge_restarts in [false, true] {
+            let sequential = Arc::new(Mutex::new(Recorder::default()));
+            let mut parser = LogParser::new();
+            parser.set_merge_restarts(merge_restarts);
+            parser.add_handler(sequential.clone());
+            parser.parse_reader(log.as_bytes()).unwrap();
+
+            let parallel = Arc::new(Mutex::new(Recorder::default()));
+            let mut parser = LogParser::new();
+            parser.set_merge_restarts(merge_restarts);
+            parser.add_handler(parallel.clone());
+            parser.parse_parallel(log, 4).unwrap();
+
+            assert_eq!(parallel.lock().unwrap().0, sequential.lock().unwrap().0);
+        }
+    }
+}
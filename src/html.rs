@@ -0,0 +1,291 @@
+use crate::collector::Counts;
+use crate::report::{capitalize, sorted_counts, Ranking, Reporter};
+use crate::{charts, format_duration, Action, Game, OverallStats, PlayerStats};
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin:0.5em 0 1.5em}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left}\
+th{background:#eee}td.num{text-align:right}\
+section{border-top:2px solid #888;margin-top:2em}";
+
+/// A self-contained HTML report with a match list, one section per match
+/// and the overall leaderboard.
+#[derive(Debug, Clone, Default)]
+pub struct HtmlReporter {
+    pub ranking: Ranking,
+}
+
+impl Reporter for HtmlReporter {
+    fn write_report(&self, games: &[Game], overall: &OverallStats, out: &mut dyn Write) -> io::Result<()> {
+        write_report(games, overall, &self.ranking, out)
+    }
+}
+
+fn write_report(games: &[Game], overall: &OverallStats, ranking: &Ranking, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>Match Report</title>\n<style>{}</style>\n</head>\n<body>", STYLE)?;
+    writeln!(out, "<h1>Match Report</h1>")?;
+    writeln!(out, "<p>Parsed {} games. <a href=\"#leaderboard\">Overall leaderboard</a></p>", games.len())?;
+
+    writeln!(out, "<h2>Matches</h2>\n<table>")?;
+    writeln!(out, "<tr><th>Game</th><th>Map</th><th>Mode</th><th>Duration</th><th>Winner</th><th>Server</th><th>Status</th><th>Source</th></tr>")?;
+    for game in games {
+        writeln!(
+            out,
+            "<tr><td><a href=\"#game-{id}\">Game {id}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(game.map_name().unwrap_or("-")),
+            game.game_type().unwrap_or("-"),
+            game.duration().map(format_duration).unwrap_or_else(|| "-".to_string()),
+            escape(&game.winner().unwrap_or_else(|| "-".to_string())),
+            escape(game.server.as_deref().unwrap_or("-")),
+            game.status(),
+            escape(&source(game)),
+            id = game.id,
+        )?;
+    }
+    writeln!(out, "</table>")?;
+
+    for game in games {
+        write_game(game, out)?;
+    }
+
+    write_leaderboard(games, overall, ranking, out)?;
+
+    writeln!(out, "</body>\n</html>")
+}
+
+/// Where the game was read from, e.g. `games.log:12-340`.
+fn source(game: &Game) -> String {
+    match &game.source {
+        Some(path) => format!("{}:{}-{}", path.display(), game.lines.start, game.lines.end.saturating_sub(1)),
+        None => "-".to_string(),
+    }
+}
+
+fn write_game(game: &Game, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "<section id=\"game-{}\">", game.id)?;
+    writeln!(
+        out,
+        "<h2>Game {}: {}</h2>\n<p>{} &middot; {} &middot; {}</p>",
+        game.id,
+        escape(game.map_name().unwrap_or("unknown map")),
+        game.game_type().unwrap_or("unknown mode"),
+        game.duration().map(format_duration).unwrap_or_else(|| "-".to_string()),
+        game.status(),
+    )?;
+
+    let scoreboard = game.get_scoreboard();
+    writeln!(out, "<h3>Scoreboard</h3>\n<table>")?;
+    writeln!(out, "<tr><th>#</th><th>Player</th><th>Score</th><th>Kills</th><th>Deaths</th><th>Suicides</th></tr>")?;
+    for (rank, player) in scoreboard.iter().enumerate() {
+        writeln!(
+            out,
+            "<tr><td class=\"num\">{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            rank + 1,
+            escape(&player.name),
+            player.score,
+            player.kills,
+            player.deaths,
+            player.suicides,
+        )?;
+    }
+    writeln!(out, "</table>")?;
+
+    let kills = game.get_kills();
+    if !kills.is_empty() {
+        write!(out, "{}", charts::frags_over_time(game))?;
+    }
+
+    writeln!(out, "<h3>Kill feed ({})</h3>", kills.len())?;
+    if !kills.is_empty() {
+        writeln!(out, "<table>\n<tr><th>Time</th><th>Killer</th><th>Victim</th><th>Means</th></tr>")?;
+        for event in kills {
+            if let Action::Kill { player_name, victim_name, method, .. } = &event.action {
+                writeln!(
+                    out,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    escape(&event.timestamp),
+                    escape(player_name),
+                    escape(victim_name),
+                    escape(method),
+                )?;
+            }
+        }
+        writeln!(out, "</table>")?;
+    }
+
+    if !game.kills_by_means.is_empty() {
+        writeln!(out, "<h3>Weapons</h3>\n<table>\n<tr><th>Means</th><th>Kills</th></tr>")?;
+        for (method, count) in sorted_counts(&game.kills_by_means) {
+            writeln!(out, "<tr><td>{}</td><td class=\"num\">{}</td></tr>", escape(method), count)?;
+        }
+        writeln!(out, "</table>")?;
+        write!(out, "{}", charts::kills_by_means(&format!("Game {}: kills by means", game.id), &game.kills_by_means))?;
+    }
+
+    for (name, counts) in game.stats.iter().filter(|(_, counts)| !counts.is_empty()) {
+        writeln!(out, "<h3>{}</h3>", escape(&capitalize(name)))?;
+        write_counts_table(counts, out)?;
+    }
+
+    let matrix = game.kill_matrix();
+    if !matrix.is_empty() {
+        let killers: BTreeSet<&str> = matrix.keys().map(|(k, _)| k.as_str()).collect();
+        let victims: BTreeSet<&str> = matrix.keys().map(|(_, v)| v.as_str()).collect();
+
+        writeln!(out, "<h3>Kill matrix</h3>\n<table>")?;
+        write!(out, "<tr><th>Killer \\ Victim</th>")?;
+        for victim in &victims {
+            write!(out, "<th>{}</th>", escape(victim))?;
+        }
+        writeln!(out, "</tr>")?;
+        for killer in &killers {
+            write!(out, "<tr><th>{}</th>", escape(killer))?;
+            for victim in &victims {
+                match matrix.get(&(killer.to_string(), victim.to_string())) {
+                    Some(count) => write!(out, "<td class=\"num\">{}</td>", count)?,
+                    None => write!(out, "<td></td>")?,
+                }
+            }
+            writeln!(out, "</tr>")?;
+        }
+        writeln!(out, "</table>")?;
+    }
+
+    writeln!(out, "</section>")
+}
+
+fn write_counts_table(counts: &Counts, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "<table>\n<tr><th>Name</th><th>Count</th></tr>")?;
+    for (key, count) in sorted_counts(counts) {
+        writeln!(out, "<tr><td>{}</td><td class=\"num\">{}</td></tr>", escape(key), count)?;
+    }
+    writeln!(out, "</table>")
+}
+
+fn write_leaderboard(games: &[Game], overall: &OverallStats, ranking: &Ranking, out: &mut dyn Write) -> io::Result<()> {
+    let leaderboard = ranking.rank_overall(overall);
+
+    writeln!(out, "<section id=\"leaderboard\">\n<h2>Overall leaderboard</h2>")?;
+    write_ranking_table(&leaderboard, out)?;
+
+    if !overall.interrupted.is_empty() {
+        let games: Vec<String> = overall
+            .interrupted
+            .iter()
+            .map(|(id, interruption)| format!("<a href=\"#game-{id}\">Game {id}</a> ({})", interruption.describe()))
+            .collect();
+        writeln!(out, "<p>Interrupted games, not counted: {}</p>", games.join(", "))?;
+    }
+    if !overall.excluded.is_empty() {
+        let games: Vec<String> = overall
+            .excluded
+            .iter()
+            .map(|id| format!("<a href=\"#game-{id}\">Game {id}</a>"))
+            .collect();
+        writeln!(out, "<p>Games excluded by the inclusion rules: {}</p>", games.join(", "))?;
+    }
+
+    if !overall.kills_by_means.is_empty() {
+        write!(out, "{}", charts::kills_by_means("Overall kills by means", &overall.kills_by_means))?;
+    }
+    for (name, counts) in overall.stats.iter().filter(|(_, counts)| !counts.is_empty()) {
+        writeln!(out, "<h3>{}</h3>", escape(&capitalize(name)))?;
+        write_counts_table(counts, out)?;
+    }
+    for player in &leaderboard {
+        if let Some(svg) = charts::player_weapons(&player.name, games) {
+            write!(out, "{}", svg)?;
+        }
+    }
+
+    writeln!(out, "</section>")?;
+
+    if overall.servers.len() > 1 {
+        writeln!(out, "<section id=\"servers\">\n<h2>Servers</h2>")?;
+        for (server, stats) in &overall.servers {
+            writeln!(out, "<h3>{}</h3>", escape(server))?;
+            write_ranking_table(&ranking.rank_overall(stats), out)?;
+        }
+        writeln!(out, "</section>")?;
+    }
+
+    Ok(())
+}
+
+fn write_ranking_table(leaderboard: &[PlayerStats], out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "<table>")?;
+    writeln!(out, "<tr><th>#</th><th>Player</th><th>Kills</th><th>Deaths</th><th>Suicides</th><th>K/D</th><th>Score</th><th>Rating</th></tr>")?;
+    for (rank, player) in leaderboard.iter().enumerate() {
+        writeln!(
+            out,
+            "<tr><td class=\"num\">{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td>\
+             <td class=\"num\">{:.2}</td><td class=\"num\">{}</td><td class=\"num\">{:.0}</td></tr>",
+            rank + 1,
+            escape(&player.name),
+            player.kills,
+            player.deaths,
+            player.suicides,
+            player.kd_ratio(),
+            player.score,
+            player.rating,
+        )?;
+    }
+    writeln!(out, "</table>")
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogParser;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("<world> & \"Bob\""), "&lt;world&gt; &amp; &quot;Bob&quot;");
+    }
+
+    #[test]
+    fn test_write_report() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test\\g_gametype\\0\\mapname\\q3dm17
+0:01 ClientUserinfoChanged: 2 n\\Alice\\t\\0
+0:02 Kill: 2 2 3: Alice killed Bob by MOD_RAILGUN
+0:03 Kill: 1022 3 22: <world> killed Bob by MOD_FALLING
+1:30 ShutdownGame:
+";
+        let mut parser = LogParser::new();
+        parser.parse_reader(log.as_bytes()).unwrap();
+
+        let mut out = Vec::new();
+        HtmlReporter::default()
+            .write_report(parser.get_games(), parser.get_overall_stats(), &mut out)
+            .unwrap();
+        let html = String::from_utf8(out).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<a href=\"#game-1\">Game 1</a></td><td>q3dm17</td><td>Free For All</td><td>1:30</td><td>Alice</td>"));
+        assert!(html.contains("<section id=\"game-1\">"));
+        assert!(html.contains("<th>&lt;world&gt;</th>"));
+        assert!(html.contains("<td>MOD_RAILGUN</td><td class=\"num\">1</td>"));
+        assert!(html.contains("<section id=\"leaderboard\">"));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+}
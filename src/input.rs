@@ -0,0 +1,330 @@
+use crate::{open_file, ParseError};
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// Compression formats recognised by their magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Xz,
+}
+
+impl Compression {
+    pub fn detect(header: &[u8]) -> Compression {
+        if header.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if header.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else if header.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Compression::Xz
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/// Wraps `reader` in a streaming decoder if its first bytes identify a
+/// gzip, zstd or xz stream; plain text is passed through unchanged.
+pub fn decompress<'a, R: Read + 'a>(reader: R) -> io::Result<Box<dyn BufRead + 'a>> {
+    let mut reader = BufReader::new(reader);
+    let compression = Compression::detect(reader.fill_buf()?);
+
+    match compression {
+        Compression::None => Ok(Box::new(reader)),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Ok(Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)))),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Ok(Box::new(BufReader::new(zstd::stream::read::Decoder::with_buffer(reader)?))),
+        #[cfg(feature = "xz")]
+        Compression::Xz => Ok(Box::new(BufReader::new(xz2::bufread::XzDecoder::new_multi_decoder(reader)))),
+        #[allow(unreachable_patterns)]
+        unsupported => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("log is {:?}-compressed but support for it was not compiled in", unsupported),
+        )),
+    }
+}
+
+/// Decodes a log line. Bytes that aren't valid UTF-8, like the high-bit
+/// characters Quake 3 allows in player names, are read as Latin-1 instead of
+/// failing; the result is `Cow::Owned` only for such repaired lines.
+pub fn decode_line(bytes: &[u8]) -> Cow<'_, str> {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Cow::Borrowed(text),
+        Err(_) => {
+            let mut text = String::with_capacity(bytes.len() * 2);
+            for chunk in bytes.utf8_chunks() {
+                text.push_str(chunk.valid());
+                text.extend(chunk.invalid().iter().map(|&b| char::from(b)));
+            }
+            Cow::Owned(text)
+        }
+    }
+}
+
+/// The lines of `bytes` without their line endings, like `str::lines`.
+pub(crate) fn lines(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    bytes.split_inclusive(|&b| b == b'\n').map(|line| {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        line.strip_suffix(b"\r").unwrap_or(line)
+    })
+}
+
+/// Decodes a whole log like `decode_line`, returning it with the number of
+/// lines that needed repair. Valid UTF-8 is borrowed as is.
+pub fn decode_log(bytes: &[u8]) -> (Cow<'_, str>, usize) {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return (Cow::Borrowed(text), 0);
+    }
+
+    let mut text = String::with_capacity(bytes.len() * 2);
+    let mut repaired = 0;
+    for line in bytes.split_inclusive(|&b| b == b'\n') {
+        let decoded = decode_line(line);
+        if let Cow::Owned(_) = decoded {
+            repaired += 1;
+        }
+        text.push_str(&decoded);
+    }
+    (Cow::Owned(text), repaired)
+}
+
+/// A log path from the command line, optionally labelled with the server it
+/// came from as `LABEL=PATH`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    pub path: PathBuf,
+    pub label: Option<String>,
+}
+
+impl Input {
+    pub fn is_stdin(&self) -> bool {
+        self.path.as_os_str() == "-"
+    }
+
+    /// Opens the log, reading stdin for `-`, and decompresses compressed
+    /// input on the fly.
+    pub fn open(&self) -> Result<Box<dyn BufRead>, ParseError> {
+        if self.is_stdin() {
+            decompress(io::stdin().lock()).map_err(|e| ParseError::io(None, None, e))
+        } else {
+            open_file(&self.path)
+        }
+    }
+}
+
+impl FromStr for Input {
+    type Err = Infallible;
+
+    fn from_str(arg: &str) -> Result<Self, Self::Err> {
+        // A file that happens to contain `=` is still taken as a plain path
+        if let Some((label, path)) = arg.split_once('=') {
+            if !label.is_empty() && !label.contains(std::path::is_separator) && !Path::new(arg).exists() {
+                return Ok(Input {
+                    path: PathBuf::from(path),
+                    label: Some(label.to_string()),
+                });
+            }
+        }
+        Ok(Input { path: PathBuf::from(arg), label: None })
+    }
+}
+
+/// Expands the logs given on the command line into the files to parse.
+///
+/// Glob patterns are expanded and directories contribute the files directly
+/// inside them, each keeping the label of the input it came from. The result
+/// is sorted oldest first by modification time (then by path), so rotated
+/// logs are merged in the order they were written. `-` (stdin) is passed
+/// through but can't be combined with other inputs.
+pub fn expand_inputs(inputs: &[Input]) -> io::Result<Vec<Input>> {
+    if inputs.iter().any(Input::is_stdin) {
+        if inputs.len() > 1 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "`-` (stdin) can't be combined with other inputs"));
+        }
+        return Ok(inputs.to_vec());
+    }
+
+    let mut files = Vec::new();
+    for input in inputs {
+        let text = input.path.to_string_lossy();
+        if text.contains(['*', '?', '[']) && !input.path.exists() {
+            let paths = glob::glob(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            let before = files.len();
+            for path in paths {
+                add_path(path?, &input.label, &mut files)?;
+            }
+            if files.len() == before {
+                return Err(io::Error::new(io::ErrorKind::NotFound, format!("no files match {}", text)));
+            }
+        } else {
+            add_path(input.path.clone(), &input.label, &mut files)?;
+        }
+    }
+
+    let mut dated = files
+        .into_iter()
+        .map(|input| {
+            let metadata = fs::metadata(&input.path)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", input.path.display(), e)))?;
+            Ok((metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH), input))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    dated.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.path.cmp(&b.1.path)));
+    dated.dedup_by(|a, b| a.1.path == b.1.path);
+    Ok(dated.into_iter().map(|(_, input)| input).collect())
+}
+
+fn add_path(path: PathBuf, label: &Option<String>, files: &mut Vec<Input>) -> io::Result<()> {
+    if !path.is_dir() {
+        files.push(Input { path, label: label.clone() });
+        return Ok(());
+    }
+    for entry in fs::read_dir(&path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() && !is_hidden(&entry.path()) {
+            files.push(Input { path: entry.path(), label: label.clone() });
+        }
+    }
+    Ok(())
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogParser;
+    #[cfg(any(feature = "gzip", feature = "xz"))]
+    use std::io::Write;
+
+    const LOG: &str = "\
+0:00 InitGame: \\sv_hostname\\Test Server
+0:01 Kill: 1 2 3: Alice killed Bob by MOD_RAILGUN
+0:02 ShutdownGame:
+";
+
+    fn parse_compressed(data: &[u8]) -> LogParser {
+        let mut parser = LogParser::new();
+        parser.parse_reader(decompress(data).unwrap()).unwrap();
+        parser
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Compression::detect(b"\x1f\x8b\x08\x00"), Compression::Gzip);
+        assert_eq!(Compression::detect(b"\x28\xb5\x2f\xfd\x00"), Compression::Zstd);
+        assert_eq!(Compression::detect(b"\xfd7zXZ\x00\x00"), Compression::Xz);
+        assert_eq!(Compression::detect(b"  0:00 InitGame:"), Compression::None);
+        assert_eq!(Compression::detect(b""), Compression::None);
+    }
+
+    #[test]
+    fn test_plain_text_passes_through() {
+        let parser = parse_compressed(LOG.as_bytes());
+        assert_eq!(parser.get_games().len(), 1);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip() {
+        // Two members, as produced by appending to a rotated .gz file
+        let mut data = Vec::new();
+        for _ in 0..2 {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(LOG.as_bytes()).unwrap();
+            data.extend(encoder.finish().unwrap());
+        }
+
+        let parser = parse_compressed(&data);
+        assert_eq!(parser.get_games().len(), 2);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd() {
+        let data = zstd::encode_all(LOG.as_bytes(), 0).unwrap();
+
+        let parser = parse_compressed(&data);
+        assert_eq!(parser.get_games().len(), 1);
+        assert_eq!(parser.get_overall_stats().killers.get("Alice"), Some(&1));
+    }
+
+    #[cfg(feature = "xz")]
+    #[test]
+    fn test_xz() {
+        let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+        encoder.write_all(LOG.as_bytes()).unwrap();
+        let data = encoder.finish().unwrap();
+
+        let parser = parse_compressed(&data);
+        assert_eq!(parser.get_games().len(), 1);
+    }
+
+    #[test]
+    fn test_decode_line() {
+        assert!(matches!(decode_line(b"0:00 ClientConnect: 2"), Cow::Borrowed("0:00 ClientConnect: 2")));
+        // Latin-1 bytes next to valid UTF-8 keep both readable
+        assert_eq!(decode_line(b"n\\Jo\xe3o \xe2\x98\x85\\t"), "n\\João ★\\t");
+
+        let (text, repaired) = decode_log(b"a\n\xff\nb\xe9\nc");
+        assert_eq!((text.as_ref(), repaired), ("a\n\u{ff}\nb\u{e9}\nc", 2));
+    }
+
+    #[test]
+    fn test_parse_input_label() {
+        let input: Input = "eu-1=logs/games.log".parse().unwrap();
+        assert_eq!(input.label.as_deref(), Some("eu-1"));
+        assert_eq!(input.path, PathBuf::from("logs/games.log"));
+
+        let input: Input = "logs/a=b.log".parse().unwrap();
+        assert_eq!(input.label, None);
+        assert_eq!(input.path, PathBuf::from("logs/a=b.log"));
+    }
+
+    #[test]
+    fn test_expand_inputs() {
+        let dir = std::env::temp_dir().join(format!("log_parser_inputs_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("rotated")).unwrap();
+
+        let now = SystemTime::now();
+        let write = |name: &str, age: u64| {
+            let path = dir.join(name);
+            fs::write(&path, LOG).unwrap();
+            let file = fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(now - std::time::Duration::from_secs(age)).unwrap();
+            path
+        };
+        let current = write("games.log", 0);
+        let old = write("rotated/games.log.2", 200);
+        let older = write("rotated/games.log.1", 300);
+        write("rotated/.hidden", 100);
+
+        let input = |arg: PathBuf| Input { path: arg, label: None };
+        let inputs = vec![input(current.clone()), input(dir.join("rotated")), input(dir.join("*.log"))];
+        let paths: Vec<PathBuf> = expand_inputs(&inputs).unwrap().into_iter().map(|i| i.path).collect();
+        assert_eq!(paths, vec![older, old, current]);
+
+        let labelled: Input = format!("eu={}", dir.join("rotated").display()).parse().unwrap();
+        let expanded = expand_inputs(&[labelled]).unwrap();
+        assert_eq!(expanded.len(), 2);
+        assert!(expanded.iter().all(|i| i.label.as_deref() == Some("eu")));
+
+        let stdin = input(PathBuf::from("-"));
+        assert!(expand_inputs(&[input(dir.join("*.missing"))]).is_err());
+        assert!(expand_inputs(&[stdin.clone(), input(dir.join("games.log"))]).is_err());
+        assert_eq!(expand_inputs(std::slice::from_ref(&stdin)).unwrap(), vec![stdin]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
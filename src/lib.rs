@@ -0,0 +1,1915 @@
+//! Parser for Quake 3 Arena server logs (`games.log`).
+//!
+//! `LogParser` turns a log into `Game`s made of `GameEvent`s and keeps
+//! `OverallStats` across them; the `report` modules render both as text,
+//! Markdown or HTML.
+//!
+//! ```
+//! use log_parser::LogParser;
+//!
+//! let log = "0:00 InitGame: \\sv_hostname\\Test\n\
+//!            0:01 Kill: 2 3 7: Alice killed Bob by MOD_ROCKET_SPLASH\n\
+//!            0:02 ShutdownGame:\n";
+//! let mut parser = LogParser::new();
+//! parser.parse_reader(log.as_bytes())?;
+//!
+//! assert_eq!(parser.get_games()[0].killers["Alice"], 1);
+//! # Ok::<(), log_parser::ParseError>(())
+//! ```
+
+pub mod charts;
+pub mod collector;
+pub mod diagnostic;
+pub mod error;
+pub mod follow;
+pub mod handler;
+pub mod html;
+pub mod input;
+pub mod markdown;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+mod parallel;
+pub mod report;
+pub mod rules;
+pub mod stream;
+pub mod validate;
+
+pub use collector::Collector;
+pub use diagnostic::{Diagnostic, Malformed};
+pub use error::ParseError;
+pub use handler::EventHandler;
+pub use rules::InclusionRules;
+pub use stream::GameStream;
+
+use collector::{Killers, KillsByMeans};
+use regex::Regex;
+use report::{Reporter, TextReporter};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameEvent {
+    pub timestamp: String,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    InitGame { details: String },
+    ShutdownGame,
+    ClientConnect { player_id: u32 },
+    ClientUserinfoChanged { player_id: u32, info: String },
+    ClientBegin { player_id: u32 },
+    Item { item_id: u32, description: String },
+    Kill {
+        kill_id: u32,
+        player_id: u32,
+        victim_id: u32,
+        player_name: String,
+        victim_name: String,
+        method: String,
+    },
+    ClientDisconnect { player_id: u32 },
+    Other { action_name: String, details: String },
+}
+
+/// Writes the event as a log line, e.g. ` 20:34 ClientConnect: 2`, with the
+/// timestamp right-aligned the way the server writes it. Parsing the line
+/// gives back an equal event.
+impl fmt::Display for GameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>6} {}", self.timestamp, self.action)
+    }
+}
+
+/// Writes the action as it follows the timestamp in a log line.
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::InitGame { details } => write_event(f, "InitGame", details),
+            Action::ShutdownGame => f.write_str("ShutdownGame:"),
+            Action::ClientConnect { player_id } => write!(f, "ClientConnect: {}", player_id),
+            Action::ClientUserinfoChanged { player_id, info } => {
+                write!(f, "ClientUserinfoChanged: {} {}", player_id, info)
+            }
+            Action::ClientBegin { player_id } => write!(f, "ClientBegin: {}", player_id),
+            Action::Item { item_id, description } => write!(f, "Item: {} {}", item_id, description),
+            Action::Kill { kill_id, player_id, victim_id, player_name, victim_name, method } => write!(
+                f,
+                "Kill: {} {} {}: {} killed {} by {}",
+                kill_id, player_id, victim_id, player_name, victim_name, method
+            ),
+            Action::ClientDisconnect { player_id } => write!(f, "ClientDisconnect: {}", player_id),
+            Action::Other { action_name, details } => write_event(f, action_name, details),
+        }
+    }
+}
+
+/// Writes `name: details`, or just `name:` when there are no details.
+fn write_event(f: &mut fmt::Formatter<'_>, name: &str, details: &str) -> fmt::Result {
+    if details.is_empty() {
+        write!(f, "{}:", name)
+    } else {
+        write!(f, "{}: {}", name, details)
+    }
+}
+
+/// A parsed log line that borrows its text from the line instead of
+/// allocating; `to_event` makes an owned `GameEvent` of it when it's kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventRef<'a> {
+    pub timestamp: &'a str,
+    pub action: ActionRef<'a>,
+}
+
+/// Borrowed counterpart of `Action`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionRef<'a> {
+    InitGame { details: &'a str },
+    ShutdownGame,
+    ClientConnect { player_id: u32 },
+    ClientUserinfoChanged { player_id: u32, info: &'a str },
+    ClientBegin { player_id: u32 },
+    Item { item_id: u32, description: &'a str },
+    Kill {
+        kill_id: u32,
+        player_id: u32,
+        victim_id: u32,
+        player_name: &'a str,
+        victim_name: &'a str,
+        method: &'a str,
+    },
+    ClientDisconnect { player_id: u32 },
+    Other { action_name: &'a str, details: &'a str },
+}
+
+impl<'a> EventRef<'a> {
+    /// Parses a log line such as `20:34 ClientConnect: 2`.
+    pub fn parse(line: &'a str) -> Option<Self> {
+        Self::parse_checked(line).ok().flatten()
+    }
+
+    /// Like `parse`, but tells lines without an event (blank lines and
+    /// `------` separators) apart from malformed ones, which fail with the
+    /// column and reason they couldn't be parsed.
+    pub fn parse_checked(line: &'a str) -> Result<Option<Self>, Malformed> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return Ok(None);
+        }
+
+        let parsed = split_timestamp(trimmed)
+            .ok_or((trimmed, "expected a `M:SS` timestamp followed by an event"))
+            .and_then(|(timestamp, content)| {
+                Ok(parse_action(content)?.map(|action| EventRef { timestamp, action }))
+            });
+        // Every failing part is a slice of `line`, so its offset is the column
+        parsed.map_err(|(part, reason)| Malformed {
+            column: part.as_ptr() as usize - line.as_ptr() as usize + 1,
+            reason,
+        })
+    }
+
+    pub fn to_event(&self) -> GameEvent {
+        GameEvent {
+            timestamp: self.timestamp.to_string(),
+            action: self.action.to_action(),
+        }
+    }
+}
+
+impl GameEvent {
+    /// Borrows the event as an `EventRef`.
+    pub fn as_event_ref(&self) -> EventRef<'_> {
+        EventRef {
+            timestamp: &self.timestamp,
+            action: self.action.as_action_ref(),
+        }
+    }
+}
+
+impl Action {
+    /// Borrowed counterpart of `ActionRef::to_action`.
+    pub fn as_action_ref(&self) -> ActionRef<'_> {
+        match self {
+            Action::InitGame { details } => ActionRef::InitGame { details },
+            Action::ShutdownGame => ActionRef::ShutdownGame,
+            Action::ClientConnect { player_id } => ActionRef::ClientConnect { player_id: *player_id },
+            Action::ClientUserinfoChanged { player_id, info } => ActionRef::ClientUserinfoChanged {
+                player_id: *player_id,
+                info,
+            },
+            Action::ClientBegin { player_id } => ActionRef::ClientBegin { player_id: *player_id },
+            Action::Item { item_id, description } => ActionRef::Item { item_id: *item_id, description },
+            Action::Kill { kill_id, player_id, victim_id, player_name, victim_name, method } => ActionRef::Kill {
+                kill_id: *kill_id,
+                player_id: *player_id,
+                victim_id: *victim_id,
+                player_name,
+                victim_name,
+                method,
+            },
+            Action::ClientDisconnect { player_id } => ActionRef::ClientDisconnect { player_id: *player_id },
+            Action::Other { action_name, details } => ActionRef::Other { action_name, details },
+        }
+    }
+}
+
+impl ActionRef<'_> {
+    pub fn to_action(&self) -> Action {
+        match *self {
+            ActionRef::InitGame { details } => Action::InitGame { details: details.to_string() },
+            ActionRef::ShutdownGame => Action::ShutdownGame,
+            ActionRef::ClientConnect { player_id } => Action::ClientConnect { player_id },
+            ActionRef::ClientUserinfoChanged { player_id, info } => Action::ClientUserinfoChanged {
+                player_id,
+                info: info.to_string(),
+            },
+            ActionRef::ClientBegin { player_id } => Action::ClientBegin { player_id },
+            ActionRef::Item { item_id, description } => Action::Item {
+                item_id,
+                description: description.to_string(),
+            },
+            ActionRef::Kill { kill_id, player_id, victim_id, player_name, victim_name, method } => Action::Kill {
+                kill_id,
+                player_id,
+                victim_id,
+                player_name: player_name.to_string(),
+                victim_name: victim_name.to_string(),
+                method: method.to_string(),
+            },
+            ActionRef::ClientDisconnect { player_id } => Action::ClientDisconnect { player_id },
+            ActionRef::Other { action_name, details } => Action::Other {
+                action_name: action_name.to_string(),
+                details: details.to_string(),
+            },
+        }
+    }
+}
+
+/// Compiles a regex on first use and reuses it afterwards.
+macro_rules! regex {
+    ($pattern:expr) => {{
+        static RE: OnceLock<Regex> = OnceLock::new();
+        RE.get_or_init(|| Regex::new($pattern).unwrap())
+    }};
+}
+
+/// Per-player statistics for a single game or aggregated over several.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerStats {
+    pub name: String,
+    /// Every kill by the player, like `killers`: killing themselves counts,
+    /// while deaths by `<world>` are nobody's kill
+    pub kills: u32,
+    pub deaths: u32,
+    /// Deaths caused by the player themselves or by `<world>`
+    pub suicides: u32,
+    pub score: i32,
+    /// Elo rating computed from who killed whom, starting at `INITIAL_RATING`
+    pub rating: f64,
+}
+
+impl PlayerStats {
+    /// Kills per death; equal to the kill count for players who never died.
+    pub fn kd_ratio(&self) -> f64 {
+        if self.deaths == 0 {
+            self.kills as f64
+        } else {
+            self.kills as f64 / self.deaths as f64
+        }
+    }
+}
+
+pub const INITIAL_RATING: f64 = 1000.0;
+const RATING_K_FACTOR: f64 = 32.0;
+
+/// Updates Elo ratings where every kill of another player counts as a win
+/// for the killer against the victim. Suicides and `<world>` kills are ignored.
+fn update_ratings<'a>(players: &mut HashMap<String, PlayerStats>, kills: impl IntoIterator<Item = &'a GameEvent>) {
+    for event in kills {
+        if let Action::Kill { player_name, victim_name, .. } = &event.action {
+            if player_name == "<world>" || player_name == victim_name {
+                continue;
+            }
+            let killer = player_entry(players, player_name).rating;
+            let victim = player_entry(players, victim_name).rating;
+            let expected = 1.0 / (1.0 + 10f64.powf((victim - killer) / 400.0));
+            let delta = RATING_K_FACTOR * (1.0 - expected);
+            player_entry(players, player_name).rating = killer + delta;
+            player_entry(players, victim_name).rating = victim - delta;
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Game {
+    pub id: u32,
+    pub events: Vec<GameEvent>,
+    pub init_details: Option<String>,
+    pub completed: bool,
+    pub kills_by_means: HashMap<String, u32>,
+    pub killers: HashMap<String, u32>,
+    /// Counts of the collectors added with `LogParser::add_collector`, by
+    /// collector name
+    pub stats: BTreeMap<String, collector::Counts>,
+    /// File the game was read from, if it came from a file
+    pub source: Option<PathBuf>,
+    /// 1-based line numbers the game spans in its input, end exclusive
+    pub lines: Range<usize>,
+    /// Label of the server the game was played on: the label given for its
+    /// input, or else the `sv_hostname` from `InitGame`
+    pub server: Option<String>,
+    /// Why the game ended without `ShutdownGame`; `None` for completed games
+    /// and the game in progress
+    pub interruption: Option<Interruption>,
+}
+
+/// How a game that never reached `ShutdownGame` was cut off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interruption {
+    /// The next `InitGame` restarted the clock: the server crashed or was
+    /// restarted
+    Crash,
+    /// The next `InitGame` kept the clock running, as on a map change
+    MapChange,
+    /// The input ended during the game
+    Truncated,
+}
+
+impl Interruption {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Interruption::Crash => "crash",
+            Interruption::MapChange => "map change",
+            Interruption::Truncated => "truncated",
+        }
+    }
+}
+
+impl Game {
+    pub fn new(id: u32) -> Self {
+        Game {
+            id,
+            events: Vec::new(),
+            init_details: None,
+            completed: false,
+            kills_by_means: HashMap::new(),
+            killers: HashMap::new(),
+            stats: BTreeMap::new(),
+            source: None,
+            lines: 0..0,
+            server: None,
+            interruption: None,
+        }
+    }
+
+    pub fn add_event(&mut self, event: GameEvent) {
+        self.count_event(&event.as_event_ref());
+        self.events.push(event);
+    }
+
+    /// Counts an event into the game's statistics, and those of
+    /// `collectors`, while it still borrows from its line, then stores it.
+    fn push_event(&mut self, event: EventRef<'_>, collectors: &[Arc<dyn Collector>]) {
+        self.count_event(&event);
+        collector::collect(collectors, &mut self.stats, &event);
+        self.events.push(event.to_event());
+    }
+
+    /// Updates everything `add_event` keeps besides the events themselves.
+    fn count_event(&mut self, event: &EventRef<'_>) {
+        match event.action {
+            ActionRef::InitGame { details } => self.init_details = Some(details.to_string()),
+            ActionRef::ShutdownGame => self.completed = true,
+            _ => {
+                KillsByMeans.collect(event, &mut self.kills_by_means);
+                Killers.collect(event, &mut self.killers);
+            }
+        }
+    }
+
+    /// Appends the events of `restart`, the same game resumed after a crash,
+    /// counting them into this game's `stats` with `collectors`.
+    fn append(&mut self, restart: Game, collectors: &[Arc<dyn Collector>]) {
+        for event in &restart.events {
+            let event = event.as_event_ref();
+            self.count_event(&event);
+            collector::collect(collectors, &mut self.stats, &event);
+        }
+        self.events.extend(restart.events);
+        self.lines.end = restart.lines.end;
+        // Classified again once the resumed game ends
+        self.interruption = None;
+    }
+
+    /// Classifies how the game was cut off by `next` starting.
+    fn interruption_by(&self, next: &Game) -> Interruption {
+        let last = self.events.last().and_then(|e| parse_timestamp(&e.timestamp));
+        let next_start = next.events.first().and_then(|e| parse_timestamp(&e.timestamp));
+        match (last, next_start) {
+            (Some(last), Some(next_start)) if next_start < last => Interruption::Crash,
+            _ => Interruption::MapChange,
+        }
+    }
+
+    /// `completed`, `incomplete` for the game in progress, or the cause of
+    /// the interruption.
+    pub fn status(&self) -> &'static str {
+        match self.interruption {
+            Some(interruption) => interruption.describe(),
+            None if self.completed => "completed",
+            None => "incomplete",
+        }
+    }
+
+    pub fn get_players(&self) -> HashMap<u32, String> {
+        let mut players = HashMap::new();
+
+        for event in &self.events {
+            if let Action::ClientUserinfoChanged { player_id, info } = &event.action {
+                if let Some(name) = extract_player_name(info) {
+                    players.insert(*player_id, name.to_string());
+                }
+            }
+        }
+
+        players
+    }
+
+    /// Ids of the clients that are bots, whose userinfo has a `skill` level.
+    pub fn get_bots(&self) -> HashSet<u32> {
+        let mut bots = HashSet::new();
+        for event in &self.events {
+            if let Action::ClientUserinfoChanged { player_id, info } = &event.action {
+                if info.split('\\').step_by(2).any(|key| key == "skill") {
+                    bots.insert(*player_id);
+                } else {
+                    bots.remove(player_id);
+                }
+            }
+        }
+        bots
+    }
+
+    /// Whether the game hit a time, frag or capture limit.
+    pub fn reached_exit(&self) -> bool {
+        self.events
+            .iter()
+            .any(|e| matches!(&e.action, Action::Other { action_name, .. } if action_name == "Exit"))
+    }
+
+    pub fn get_kills(&self) -> Vec<&GameEvent> {
+        self.events
+            .iter()
+            .filter(|e| matches!(e.action, Action::Kill { .. }))
+            .collect()
+    }
+
+    /// Looks up a server variable from the `InitGame` info string, e.g. `mapname`.
+    pub fn server_var(&self, key: &str) -> Option<&str> {
+        let details = self.init_details.as_deref()?;
+        let mut parts = details.trim_start_matches('\\').split('\\');
+        while let (Some(k), Some(v)) = (parts.next(), parts.next()) {
+            if k == key {
+                return Some(v);
+            }
+        }
+        None
+    }
+
+    pub fn map_name(&self) -> Option<&str> {
+        self.server_var("mapname")
+    }
+
+    pub fn game_type(&self) -> Option<&'static str> {
+        // Some servers log the value as "= 0"
+        let value = self.server_var("g_gametype")?.trim_start_matches('=').trim();
+        let name = match value.parse::<u32>().ok()? {
+            0 => "Free For All",
+            1 => "Tournament",
+            2 => "Single Player",
+            3 => "Team Deathmatch",
+            4 => "Capture The Flag",
+            _ => "Unknown",
+        };
+        Some(name)
+    }
+
+    /// Whether the game was played on `server` (its label or `sv_hostname`),
+    /// or on any server if `None`.
+    pub fn played_on(&self, server: Option<&str>) -> bool {
+        server.is_none() || self.server.as_deref() == server
+    }
+
+    /// Match length in seconds, from the first to the last recorded event.
+    /// A game merged across a restart is timed up to the restart and again
+    /// from its new `InitGame`.
+    pub fn duration(&self) -> Option<u32> {
+        self.elapsed_times()?.last().copied()
+    }
+
+    /// Seconds into the match at each event, timed like `duration`: the
+    /// segments of a game merged across a restart follow each other.
+    pub fn elapsed_times(&self) -> Option<Vec<u32>> {
+        let mut times = Vec::with_capacity(self.events.len());
+        let mut offset = 0;
+        let mut start = parse_timestamp(&self.events.first()?.timestamp)?;
+        let mut end = start;
+        for event in &self.events {
+            let time = parse_timestamp(&event.timestamp)?;
+            if matches!(event.action, Action::InitGame { .. }) && !times.is_empty() {
+                offset += end.saturating_sub(start);
+                start = time;
+            }
+            end = time;
+            times.push(offset + time.saturating_sub(start));
+        }
+        Some(times)
+    }
+
+    /// Final scores reported by the server after `Exit`, in log order.
+    pub fn get_scores(&self) -> Vec<(String, i32)> {
+        self.events
+            .iter()
+            .filter_map(|e| match &e.action {
+                Action::Other { action_name, details } if action_name == "score" => {
+                    parse_score(details).map(|(name, score)| (name.to_string(), score))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Builds the scoreboard, best player first.
+    ///
+    /// Scores come from the server's `score:` lines when the game reached
+    /// `Exit`; otherwise they are computed as kills minus suicides.
+    pub fn get_scoreboard(&self) -> Vec<PlayerStats> {
+        let mut stats: HashMap<String, PlayerStats> = HashMap::new();
+
+        for name in self.get_players().values() {
+            player_entry(&mut stats, name);
+        }
+
+        for event in &self.events {
+            if let Action::Kill { player_name, victim_name, .. } = &event.action {
+                let victim = player_entry(&mut stats, victim_name);
+                victim.deaths += 1;
+                if player_name == "<world>" || player_name == victim_name {
+                    victim.suicides += 1;
+                    victim.score -= 1;
+                }
+                if player_name != "<world>" {
+                    let killer = player_entry(&mut stats, player_name);
+                    killer.kills += 1;
+                    if player_name != victim_name {
+                        killer.score += 1;
+                    }
+                }
+            }
+        }
+
+        for (name, score) in self.get_scores() {
+            player_entry(&mut stats, &name).score = score;
+        }
+        update_ratings(&mut stats, self.get_kills());
+
+        let mut scoreboard: Vec<PlayerStats> = stats.into_values().collect();
+        scoreboard.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(b.kills.cmp(&a.kills))
+                .then(a.name.cmp(&b.name))
+        });
+        scoreboard
+    }
+
+    pub fn winner(&self) -> Option<String> {
+        self.get_scoreboard().into_iter().next().map(|p| p.name)
+    }
+
+    /// Counts kills per (killer, victim) pair, including `<world>` kills.
+    pub fn kill_matrix(&self) -> HashMap<(String, String), u32> {
+        let mut matrix = HashMap::new();
+        for event in &self.events {
+            if let Action::Kill { player_name, victim_name, .. } = &event.action {
+                *matrix
+                    .entry((player_name.clone(), victim_name.clone()))
+                    .or_insert(0) += 1;
+            }
+        }
+        matrix
+    }
+}
+
+/// Statistics aggregated over every parsed game.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OverallStats {
+    pub kills_by_means: HashMap<String, u32>,
+    pub killers: HashMap<String, u32>,
+    /// Totals of the collectors added with `LogParser::add_collector`, by
+    /// collector name
+    pub stats: BTreeMap<String, collector::Counts>,
+    /// Every game's scoreboard summed per player name
+    pub players: HashMap<String, PlayerStats>,
+    /// The same statistics for each server's games alone
+    pub servers: BTreeMap<String, OverallStats>,
+    /// Ids of the games that were cut off, with the cause. They are left out
+    /// of the statistics above so they don't skew the rankings
+    pub interrupted: Vec<(u32, Interruption)>,
+    /// Ids of the games left out by the `InclusionRules`
+    pub excluded: Vec<u32>,
+}
+
+impl OverallStats {
+    pub fn add_game(&mut self, game: &Game) {
+        self.add_game_with(game, &[]);
+    }
+
+    /// Like `add_game`, also merging the counts of `collectors` into `stats`.
+    pub fn add_game_with(&mut self, game: &Game, collectors: &[Arc<dyn Collector>]) {
+        // Interrupted games are only listed, so they need no scoreboard
+        let scoreboard = if game.interruption.is_none() { game.get_scoreboard() } else { Vec::new() };
+        self.add_totals(game, &scoreboard, collectors);
+        if let Some(server) = &game.server {
+            self.servers.entry(server.clone()).or_default().add_totals(game, &scoreboard, collectors);
+        }
+    }
+
+    /// Records a game that the inclusion rules leave out of the totals.
+    pub fn exclude_game(&mut self, game: &Game) {
+        self.excluded.push(game.id);
+        if let Some(server) = &game.server {
+            self.servers.entry(server.clone()).or_default().excluded.push(game.id);
+        }
+    }
+
+    fn add_totals(&mut self, game: &Game, scoreboard: &[PlayerStats], collectors: &[Arc<dyn Collector>]) {
+        if let Some(interruption) = game.interruption {
+            self.interrupted.push((game.id, interruption));
+            return;
+        }
+
+        KillsByMeans.merge(&mut self.kills_by_means, &game.kills_by_means);
+        Killers.merge(&mut self.killers, &game.killers);
+        collector::merge(collectors, &mut self.stats, &game.stats);
+
+        // Update player totals; ratings carry over from game to game
+        for player in scoreboard {
+            let total = player_entry(&mut self.players, &player.name);
+            total.kills += player.kills;
+            total.deaths += player.deaths;
+            total.suicides += player.suicides;
+            total.score += player.score;
+        }
+        update_ratings(&mut self.players, game.get_kills());
+    }
+
+    /// The players in `killers`, those with at least one kill, sorted by
+    /// kills descending and then by name.
+    pub fn leaderboard(&self) -> Vec<PlayerStats> {
+        let mut leaderboard: Vec<PlayerStats> = self.players.values().filter(|p| p.kills > 0).cloned().collect();
+        leaderboard.sort_by(|a, b| b.kills.cmp(&a.kills).then(a.name.cmp(&b.name)));
+        leaderboard
+    }
+
+    /// The statistics of every game, or of one server's games only.
+    pub fn for_server(&self, server: Option<&str>) -> Cow<'_, OverallStats> {
+        match server {
+            None => Cow::Borrowed(self),
+            Some(server) => self.servers.get(server).map_or_else(Default::default, Cow::Borrowed),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LogParser {
+    games: Vec<Game>,
+    current_game: Option<Game>,
+    game_counter: u32,
+    overall: OverallStats,
+    source: Option<PathBuf>,
+    line_number: usize,
+    server_label: Option<String>,
+    repaired_lines: usize,
+    strict: bool,
+    malformed_lines: usize,
+    diagnostics: Vec<Diagnostic>,
+    merge_restarts: bool,
+    rules: InclusionRules,
+    handlers: Vec<Box<dyn EventHandler + Send>>,
+    collectors: Vec<Arc<dyn Collector>>,
+}
+
+/// How many diagnostics are kept; beyond that malformed lines are only counted.
+const MAX_DIAGNOSTICS: usize = 1000;
+
+impl Default for LogParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogParser {
+    pub fn new() -> Self {
+        LogParser {
+            games: Vec::new(),
+            current_game: None,
+            game_counter: 0,
+            overall: OverallStats::default(),
+            source: None,
+            line_number: 0,
+            server_label: None,
+            repaired_lines: 0,
+            strict: false,
+            malformed_lines: 0,
+            diagnostics: Vec::new(),
+            merge_restarts: false,
+            rules: InclusionRules::default(),
+            handlers: Vec::new(),
+            collectors: Vec::new(),
+        }
+    }
+
+    /// Parses a log file, decompressing gzip, zstd or xz files on the fly.
+    ///
+    /// Can be called repeatedly to add more files; game ids keep counting up
+    /// and each game records the file it came from.
+    pub fn parse_file<P: AsRef<Path>>(&mut self, file_path: P) -> Result<(), ParseError> {
+        let reader = open_file(file_path.as_ref())?;
+        self.source = Some(file_path.as_ref().to_path_buf());
+        let result = self.parse_reader(reader);
+        self.source = None;
+        result
+    }
+
+    /// Parses a log from any reader, e.g. stdin or an in-memory buffer.
+    /// Lines that aren't valid UTF-8 are repaired, see `get_repaired_lines`.
+    pub fn parse_reader<R: Read>(&mut self, reader: R) -> Result<(), ParseError> {
+        let mut reader = BufReader::new(reader);
+        let mut line = Vec::new();
+        self.line_number = 0;
+
+        while reader.read_until(b'\n', &mut line).map_err(|e| self.io_error(e))? > 0 {
+            let decoded = self.decode_line(&line);
+            self.push_line(&decoded)?;
+            line.clear();
+        }
+
+        if let Some(game) = self.finish_game() {
+            self.games.push(game);
+        }
+
+        Ok(())
+    }
+
+    /// Parses the next line of the input, returning the game it ended, if
+    /// any, after storing it in `get_games`. Fails on a malformed line in
+    /// strict mode.
+    pub fn push_line(&mut self, line: &str) -> Result<Option<&Game>, ParseError> {
+        self.line_number += 1;
+        let game_count = self.games.len();
+        if let Some(event) = self.check_line(line)? {
+            self.handle_event(event);
+        }
+        Ok(self.games.get(game_count))
+    }
+
+    /// Parses `reader` line by line, yielding each game as soon as it ends
+    /// instead of storing it in `get_games`. Overall statistics are still
+    /// updated, so only the game in progress is held in memory.
+    pub fn stream<R: BufRead>(&mut self, reader: R) -> GameStream<'_, R> {
+        self.line_number = 0;
+        GameStream::new(self, reader)
+    }
+
+    /// Like `stream`, for a possibly compressed log file. Games record the
+    /// file they came from.
+    pub fn stream_file<P: AsRef<Path>>(&mut self, file_path: P) -> Result<GameStream<'_, Box<dyn BufRead>>, ParseError> {
+        let reader = open_file(file_path.as_ref())?;
+        let mut stream = self.stream(reader);
+        stream.source = Some(file_path.as_ref().to_path_buf());
+        Ok(stream)
+    }
+
+    /// Numbers the next line 1 again, for a new input fed line by line, e.g.
+    /// a log that was rotated while following it.
+    pub fn reset_line_number(&mut self) {
+        self.line_number = 0;
+    }
+
+    /// Labels the server of the games parsed from now on, instead of using
+    /// their `sv_hostname`.
+    pub fn set_server_label(&mut self, label: Option<String>) {
+        self.server_label = label;
+    }
+
+    /// Number of lines so far that weren't valid UTF-8 and had their invalid
+    /// bytes read as Latin-1.
+    pub fn get_repaired_lines(&self) -> usize {
+        self.repaired_lines
+    }
+
+    /// Decodes a line read as bytes with `input::decode_line`, counting it in
+    /// `get_repaired_lines` if it needed repair.
+    pub fn decode_line<'a>(&mut self, line: &'a [u8]) -> Cow<'a, str> {
+        let decoded = input::decode_line(line);
+        if let Cow::Owned(_) = decoded {
+            self.repaired_lines += 1;
+        }
+        decoded
+    }
+
+    /// Makes malformed lines an error instead of a diagnostic.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Joins a game that crashed to the game after it when that game is the
+    /// same map on the same server, i.e. the match resumed after a restart.
+    pub fn set_merge_restarts(&mut self, merge: bool) {
+        self.merge_restarts = merge;
+    }
+
+    /// Sets which games count towards the overall statistics.
+    pub fn set_inclusion_rules(&mut self, rules: InclusionRules) {
+        self.rules = rules;
+    }
+
+    /// Registers a handler to be called for every event parsed from now on.
+    pub fn add_handler<H: EventHandler + Send + 'static>(&mut self, handler: H) {
+        self.handlers.push(Box::new(handler));
+    }
+
+    /// Registers a statistic to collect for the games parsed from now on,
+    /// see `Game::stats` and `OverallStats::stats`.
+    pub fn add_collector<C: Collector + 'static>(&mut self, collector: C) {
+        self.collectors.push(Arc::new(collector));
+    }
+
+    /// Number of malformed lines skipped so far.
+    pub fn get_malformed_lines(&self) -> usize {
+        self.malformed_lines
+    }
+
+    /// The first `MAX_DIAGNOSTICS` malformed lines, in input order.
+    pub fn get_diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Parses a line, recording a diagnostic if it's malformed, or failing
+    /// with it in strict mode.
+    fn check_line<'a>(&mut self, line: &'a str) -> Result<Option<EventRef<'a>>, ParseError> {
+        match EventRef::parse_checked(line) {
+            Ok(event) => Ok(event),
+            Err(Malformed { column, reason }) => {
+                let diagnostic = Diagnostic {
+                    source: self.source.clone(),
+                    line: self.line_number,
+                    column,
+                    text: line.trim_end().to_string(),
+                    reason,
+                };
+                if self.strict {
+                    return Err(ParseError::Malformed(diagnostic));
+                }
+                self.add_diagnostic(diagnostic);
+                Ok(None)
+            }
+        }
+    }
+
+    fn add_diagnostic(&mut self, diagnostic: Diagnostic) {
+        self.malformed_lines += 1;
+        if self.diagnostics.len() < MAX_DIAGNOSTICS {
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// A read error on the line after the last one parsed.
+    fn io_error(&self, error: io::Error) -> ParseError {
+        ParseError::io(self.source.as_deref(), Some(self.line_number + 1), error)
+    }
+
+    /// Like `push_line`, but hands the game the line ended to the caller
+    /// instead of storing it in `get_games`, so only the game in progress is
+    /// held in memory. Overall statistics are still updated.
+    pub fn consume_line(&mut self, line: &str) -> Result<Option<Game>, ParseError> {
+        self.line_number += 1;
+        Ok(match self.check_line(line)? {
+            Some(event) => self.process_event(event),
+            None => None,
+        })
+    }
+
+    fn handle_event(&mut self, event: EventRef<'_>) {
+        if let Some(game) = self.process_event(event) {
+            self.games.push(game);
+        }
+    }
+
+    /// Applies an event to the game in progress and returns the game it
+    /// ended, if any.
+    fn process_event(&mut self, event: EventRef<'_>) -> Option<Game> {
+        let starts_game = matches!(event.action, ActionRef::InitGame { .. });
+        let in_game = self.current_game.is_some();
+        let game = self.build_game(event);
+
+        // Handlers hear of the event once it's stored: a `ShutdownGame`
+        // before the game it ends, an `InitGame` after the game it cuts off
+        if in_game && !starts_game {
+            if let Some(event) = game.as_ref().or(self.current_game.as_ref()).and_then(|game| game.events.last()) {
+                handler::notify(&mut self.handlers, event);
+            }
+        }
+        if let Some(game) = &game {
+            self.update_overall_stats(game);
+        }
+
+        if starts_game {
+            if let Some(init) = self.current_game.as_ref().and_then(|game| game.events.last()) {
+                handler::notify(&mut self.handlers, init);
+            }
+        }
+        game
+    }
+
+    /// Like `process_event`, but leaves the overall statistics alone.
+    fn build_game(&mut self, event: EventRef<'_>) -> Option<Game> {
+        match event.action {
+            ActionRef::InitGame { .. } => {
+                let mut new_game = Game::new(0);
+                new_game.source = self.source.clone();
+                new_game.lines = self.line_number..self.line_number + 1;
+                new_game.push_event(event, &self.collectors);
+                new_game.server = self
+                    .server_label
+                    .clone()
+                    .or_else(|| new_game.server_var("sv_hostname").map(str::to_string));
+                self.start_game(new_game)
+            }
+            ActionRef::ShutdownGame => {
+                if let Some(ref mut game) = self.current_game {
+                    game.push_event(event, &self.collectors);
+                    game.lines.end = self.line_number + 1;
+                }
+                self.current_game.take()
+            }
+            _ => {
+                if let Some(ref mut game) = self.current_game {
+                    game.push_event(event, &self.collectors);
+                    game.lines.end = self.line_number + 1;
+                }
+                None
+            }
+        }
+    }
+
+    /// Makes `game` the game in progress, numbering it, and returns the game
+    /// it cut off. A restart of a crashed game is appended to it instead
+    /// when merging restarts.
+    fn start_game(&mut self, mut game: Game) -> Option<Game> {
+        let mut previous = self.current_game.take();
+        if let Some(cut_off) = previous.as_mut().filter(|previous| !previous.completed) {
+            let interruption = cut_off.interruption_by(&game);
+            if self.merge_restarts
+                && interruption == Interruption::Crash
+                && cut_off.map_name() == game.map_name()
+                && cut_off.server == game.server
+            {
+                cut_off.append(game, &self.collectors);
+                self.current_game = previous;
+                return None;
+            }
+            cut_off.interruption = Some(interruption);
+        }
+
+        self.game_counter += 1;
+        game.id = self.game_counter;
+        self.current_game = Some(game);
+        previous
+    }
+
+    /// Ends the game in progress, e.g. at the end of the input.
+    fn finish_game(&mut self) -> Option<Game> {
+        let mut game = self.current_game.take()?;
+        if !game.completed {
+            game.interruption = Some(Interruption::Truncated);
+        }
+        self.update_overall_stats(&game);
+        Some(game)
+    }
+
+    fn update_overall_stats(&mut self, game: &Game) {
+        for handler in &mut self.handlers {
+            handler.on_game_end(game);
+        }
+
+        // Interrupted games are set aside by `add_game` before any rule applies
+        if game.interruption.is_none() && !self.rules.admits(game) {
+            self.overall.exclude_game(game);
+        } else {
+            self.overall.add_game_with(game, &self.collectors);
+        }
+    }
+
+    pub fn get_games(&self) -> &[Game] {
+        &self.games
+    }
+
+    pub fn get_overall_stats(&self) -> &OverallStats {
+        &self.overall
+    }
+
+    pub fn get_leaderboard(&self) -> Vec<PlayerStats> {
+        self.overall.leaderboard()
+    }
+
+    pub fn print_summary(&self) {
+        TextReporter::default()
+            .write_report(&self.games, &self.overall, &mut io::stdout().lock())
+            .expect("failed to write summary to stdout");
+    }
+}
+
+/// Splits `M:SS rest` into the timestamp and the rest of the line. Servers
+/// running for more than 99 minutes write 3 or more minute digits.
+fn split_timestamp(line: &str) -> Option<(&str, &str)> {
+    let colon = line.find(':')?;
+    let bytes = line.as_bytes();
+    if colon == 0
+        || !bytes[..colon].iter().all(u8::is_ascii_digit)
+        || !bytes.get(colon + 1..colon + 3)?.iter().all(u8::is_ascii_digit)
+    {
+        return None;
+    }
+
+    let (timestamp, rest) = line.split_at(colon + 3);
+    let content = rest.trim_start();
+    if content.len() == rest.len() || content.is_empty() {
+        return None;
+    }
+    Some((timestamp, content))
+}
+
+/// The part of a line that couldn't be parsed, and why.
+type Failure<'a> = (&'a str, &'static str);
+
+fn parse_action(content: &str) -> Result<Option<ActionRef<'_>>, Failure<'_>> {
+    if let Some(details) = content.strip_prefix("InitGame:") {
+        return Ok(Some(ActionRef::InitGame { details: details.trim() }));
+    }
+
+    if content == "ShutdownGame:" {
+        return Ok(Some(ActionRef::ShutdownGame));
+    }
+
+    if let Some(id_str) = content.strip_prefix("ClientConnect:") {
+        let player_id = parse_client_id(id_str)?;
+        return Ok(Some(ActionRef::ClientConnect { player_id }));
+    }
+
+    if let Some(details) = content.strip_prefix("ClientUserinfoChanged:") {
+        if let Some((id_str, info)) = details.trim().split_once(' ') {
+            let player_id = parse_client_id(id_str)?;
+            return Ok(Some(ActionRef::ClientUserinfoChanged { player_id, info }));
+        }
+    }
+
+    if let Some(id_str) = content.strip_prefix("ClientBegin:") {
+        let player_id = parse_client_id(id_str)?;
+        return Ok(Some(ActionRef::ClientBegin { player_id }));
+    }
+
+    if let Some(id_str) = content.strip_prefix("ClientDisconnect:") {
+        let player_id = parse_client_id(id_str)?;
+        return Ok(Some(ActionRef::ClientDisconnect { player_id }));
+    }
+
+    if let Some(details) = content.strip_prefix("Item:") {
+        if let Some((id_str, description)) = details.trim().split_once(' ') {
+            let item_id = id_str.parse::<u32>().map_err(|_| (id_str, "invalid item id"))?;
+            return Ok(Some(ActionRef::Item { item_id, description }));
+        }
+    }
+
+    if let Some(details) = content.strip_prefix("Kill:") {
+        return parse_kill_action(details.trim()).map(Some);
+    }
+
+    if let Some((action_name, details)) = content.split_once(':') {
+        return Ok(Some(ActionRef::Other { action_name, details: details.trim() }));
+    }
+
+    // Separator lines between games
+    if content.bytes().all(|b| b == b'-') {
+        return Ok(None);
+    }
+
+    Err((content, "expected an event name followed by `:`"))
+}
+
+fn parse_client_id(id_str: &str) -> Result<u32, Failure<'_>> {
+    let id_str = id_str.trim();
+    id_str.parse::<u32>().map_err(|_| (id_str, "invalid client id"))
+}
+
+fn parse_kill_action(details: &str) -> Result<ActionRef<'_>, Failure<'_>> {
+    const IDS: &str = "expected `<killer id> <victim id> <means id>:` in Kill";
+
+    let (ids_part, description_part) = details.split_once(':').ok_or((details, IDS))?;
+
+    let mut id_parts = ids_part.split_whitespace().map(|id| id.parse::<u32>().map_err(|_| (id, IDS)));
+    let kill_id = id_parts.next().ok_or((ids_part, IDS))??;
+    let player_id = id_parts.next().ok_or((ids_part, IDS))??;
+    let victim_id = id_parts.next().ok_or((ids_part, IDS))??;
+    if id_parts.next().is_some() {
+        return Err((ids_part, IDS));
+    }
+
+    const DESCRIPTION: &str = "expected `<killer> killed <victim> by <means>` in Kill";
+    let description = description_part.trim();
+    let captures = regex!(r"^(.+?)\s+killed\s+(.+?)\s+by\s+(.+)$")
+        .captures(description)
+        .ok_or((description, DESCRIPTION))?;
+
+    Ok(ActionRef::Kill {
+        kill_id,
+        player_id,
+        victim_id,
+        player_name: captures.get(1).ok_or((description, DESCRIPTION))?.as_str(),
+        victim_name: captures.get(2).ok_or((description, DESCRIPTION))?.as_str(),
+        method: captures.get(3).ok_or((description, DESCRIPTION))?.as_str(),
+    })
+}
+
+/// The name in a userinfo string: the first `n\` followed by anything
+/// but a backslash.
+fn extract_player_name(userinfo: &str) -> Option<&str> {
+    userinfo
+        .match_indices("n\\")
+        .filter_map(|(start, _)| userinfo[start + 2..].split('\\').next())
+        .find(|name| !name.is_empty())
+}
+
+fn player_entry<'a>(stats: &'a mut HashMap<String, PlayerStats>, name: &str) -> &'a mut PlayerStats {
+    stats.entry(name.to_string()).or_insert_with(|| PlayerStats {
+        name: name.to_string(),
+        rating: INITIAL_RATING,
+        ..Default::default()
+    })
+}
+
+/// Converts a `M:SS` log timestamp into seconds; `None` if it doesn't fit
+/// in a `u32`.
+fn parse_timestamp(timestamp: &str) -> Option<u32> {
+    let (minutes, seconds) = timestamp.split_once(':')?;
+    minutes
+        .parse::<u32>()
+        .ok()?
+        .checked_mul(60)?
+        .checked_add(seconds.parse::<u32>().ok()?)
+}
+
+fn format_duration(seconds: u32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Parses the details of a `score:` line, e.g. `20  ping: 4  client: 4 Zeh`.
+fn parse_score(details: &str) -> Option<(&str, i32)> {
+    let captures = regex!(r"^(-?\d+)\s+ping:\s+\d+\s+client:\s+\d+\s+(.+)$").captures(details)?;
+
+    let score = captures.get(1)?.as_str().parse::<i32>().ok()?;
+    let name = captures.get(2)?.as_str();
+    Some((name, score))
+}
+
+/// Opens a possibly compressed log file for parsing.
+pub(crate) fn open_file(path: &Path) -> Result<Box<dyn BufRead>, ParseError> {
+    fs::File::open(path)
+        .and_then(input::decompress)
+        .map_err(|e| ParseError::io(Some(path), None, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_client_connect() {
+        let event = EventRef::parse("20:34 ClientConnect: 2").unwrap().to_event();
+
+        assert_eq!(event.timestamp, "20:34");
+        assert!(matches!(event.action, Action::ClientConnect { player_id: 2 }));
+    }
+
+    #[test]
+    fn test_parse_client_disconnect() {
+        let event = EventRef::parse("21:10 ClientDisconnect: 2").unwrap().to_event();
+
+        assert_eq!(event.timestamp, "21:10");
+        assert!(matches!(event.action, Action::ClientDisconnect { player_id: 2 }));
+    }
+
+    #[test]
+    fn test_parse_client_userinfo_changed() {
+        let event = EventRef::parse("20:34 ClientUserinfoChanged: 2 n\\Isgalamido\\t\\0\\model\\xian/default").unwrap().to_event();
+
+        assert_eq!(event.timestamp, "20:34");
+        if let Action::ClientUserinfoChanged { player_id, info } = event.action {
+            assert_eq!(player_id, 2);
+            assert!(info.contains("Isgalamido"));
+        } else {
+            panic!("Expected ClientUserinfoChanged action");
+        }
+    }
+
+    #[test]
+    fn test_parse_client_begin() {
+        let event = EventRef::parse("20:37 ClientBegin: 2").unwrap().to_event();
+
+        assert_eq!(event.timestamp, "20:37");
+        assert!(matches!(event.action, Action::ClientBegin { player_id: 2 }));
+    }
+
+    #[test]
+    fn test_parse_item() {
+        let event = EventRef::parse("20:40 Item: 2 weapon_rocketlauncher").unwrap().to_event();
+
+        assert_eq!(event.timestamp, "20:40");
+        if let Action::Item { item_id, description } = event.action {
+            assert_eq!(item_id, 2);
+            assert_eq!(description, "weapon_rocketlauncher");
+        } else {
+            panic!("Expected Item action");
+        }
+    }
+
+    #[test]
+    fn test_parse_kill() {
+        let event = EventRef::parse("22:06 Kill: 2 3 7: Isgalamido killed Mocinha by MOD_ROCKET_SPLASH").unwrap().to_event();
+
+        assert_eq!(event.timestamp, "22:06");
+        if let Action::Kill { kill_id, player_id, victim_id, player_name, victim_name, method } = event.action {
+            assert_eq!(kill_id, 2);
+            assert_eq!(player_id, 3);
+            assert_eq!(victim_id, 7);
+            assert_eq!(player_name, "Isgalamido");
+            assert_eq!(victim_name, "Mocinha");
+            assert_eq!(method, "MOD_ROCKET_SPLASH");
+        } else {
+            panic!("Expected Kill action");
+        }
+    }
+
+    #[test]
+    fn test_parse_init_game() {
+        let event = EventRef::parse("0:00 InitGame: \\sv_floodProtect\\1\\sv_maxPing\\0").unwrap().to_event();
+
+        assert_eq!(event.timestamp, "0:00");
+        if let Action::InitGame { details } = event.action {
+            assert!(details.contains("sv_floodProtect"));
+        } else {
+            panic!("Expected InitGame action");
+        }
+    }
+
+    #[test]
+    fn test_parse_shutdown_game() {
+        let event = EventRef::parse("20:37 ShutdownGame:").unwrap().to_event();
+
+        assert_eq!(event.timestamp, "20:37");
+        assert!(matches!(event.action, Action::ShutdownGame));
+    }
+
+    #[test]
+    fn test_extract_player_name() {
+        let userinfo = "n\\Isgalamido\\t\\0\\model\\xian/default\\hmodel\\xian/default";
+        let name = extract_player_name(userinfo).unwrap();
+        assert_eq!(name, "Isgalamido");
+    }
+
+    #[test]
+    fn test_event_ref_borrows_line() {
+        let line = String::from("  1:26 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT");
+        let event = EventRef::parse(&line).unwrap();
+
+        assert_eq!(event.timestamp, "1:26");
+        let ActionRef::Kill { player_name, victim_name, method, .. } = event.action else {
+            panic!("expected a kill, got {:?}", event.action);
+        };
+        assert_eq!((player_name, victim_name, method), ("<world>", "Isgalamido", "MOD_TRIGGER_HURT"));
+        assert!(line.as_bytes().as_ptr_range().contains(&method.as_ptr()));
+
+        assert_eq!(event.to_event().as_event_ref(), event);
+    }
+
+    #[test]
+    fn test_split_timestamp() {
+        assert_eq!(split_timestamp("0:00 InitGame:"), Some(("0:00", "InitGame:")));
+        assert_eq!(split_timestamp("20:37  ClientBegin: 2"), Some(("20:37", "ClientBegin: 2")));
+        assert_eq!(split_timestamp("123:45 ShutdownGame:"), Some(("123:45", "ShutdownGame:")));
+        assert_eq!(split_timestamp(":45 ShutdownGame:"), None);
+        assert_eq!(split_timestamp("1:2 ShutdownGame:"), None);
+        assert_eq!(split_timestamp("1:23ShutdownGame:"), None);
+        assert_eq!(split_timestamp("1:23 "), None);
+        assert_eq!(split_timestamp("------"), None);
+    }
+
+    #[test]
+    fn test_select_server() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Alpha
+0:01 Kill: 2 3 7: Alice killed Bob by MOD_ROCKET
+0:02 ShutdownGame:
+0:00 InitGame: \\sv_hostname\\Beta
+0:01 Kill: 3 2 7: Bob killed Alice by MOD_ROCKET
+0:02 ShutdownGame:
+";
+        let mut parser = LogParser::new();
+        parser.parse_reader(log.as_bytes()).unwrap();
+        let games = parser.get_games();
+        let overall = parser.get_overall_stats();
+
+        assert!(games.iter().all(|game| game.played_on(None)));
+        assert_eq!(games.iter().filter(|game| game.played_on(Some("Beta"))).count(), 1);
+        assert_eq!(overall.for_server(None).killers.len(), 2);
+        assert_eq!(overall.for_server(Some("Beta")).killers.get("Bob"), Some(&1));
+        assert_eq!(*overall.for_server(Some("Gamma")), OverallStats::default());
+    }
+
+    #[test]
+    fn test_parse_long_running_server() {
+        let event = EventRef::parse("981:06 ClientConnect: 2").unwrap();
+        assert_eq!((event.timestamp, event.action), ("981:06", ActionRef::ClientConnect { player_id: 2 }));
+        assert_eq!(parse_timestamp("981:06"), Some(58866));
+        assert_eq!(parse_timestamp("80000000:00"), None);
+    }
+
+    #[test]
+    fn test_game_parser_multiple_games() {
+        let mut parser = LogParser::new();
+
+        let events = [
+            "0:00 InitGame: \\sv_hostname\\Test Server",
+            "0:01 ClientConnect: 1",
+            "0:02 ShutdownGame:",
+            "0:03 InitGame: \\sv_hostname\\Test Server 2",
+            "0:04 ClientConnect: 2",
+        ];
+
+        parser.parse_reader(events.join("\n").as_bytes()).unwrap();
+
+        if let Some(game) = parser.current_game.take() {
+            parser.games.push(game);
+        }
+
+        assert_eq!(parser.games.len(), 2);
+        assert!(parser.games[0].completed);
+        assert!(!parser.games[1].completed);
+    }
+
+    #[test]
+    fn test_game_parser_duplicate_init() {
+        let mut parser = LogParser::new();
+
+        let events = [
+            "0:00 InitGame: \\sv_hostname\\Test Server 1",
+            "0:01 ClientConnect: 1",
+            "0:02 InitGame: \\sv_hostname\\Test Server 2",
+            "0:03 ClientConnect: 2",
+            "0:04 ShutdownGame:",
+        ];
+
+        parser.parse_reader(events.join("\n").as_bytes()).unwrap();
+
+        assert_eq!(parser.games.len(), 2);
+        assert!(!parser.games[0].completed);
+        assert!(parser.games[1].completed);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut parser = LogParser::new();
+        parser.set_merge_restarts(true);
+        parser.parse_reader(&include_bytes!("../resources/qgames.log.txt")[..]).unwrap();
+
+        let games_json = serde_json::to_string(parser.get_games()).unwrap();
+        let overall_json = serde_json::to_string(parser.get_overall_stats()).unwrap();
+        let games: Vec<Game> = serde_json::from_str(&games_json).unwrap();
+        let overall: OverallStats = serde_json::from_str(&overall_json).unwrap();
+
+        assert_eq!(games.len(), parser.games.len());
+        for (a, b) in games.iter().zip(&parser.games) {
+            assert_eq!((a.id, &a.events, &a.lines, &a.server), (b.id, &b.events, &b.lines, &b.server));
+            assert_eq!((&a.killers, &a.kills_by_means, a.interruption), (&b.killers, &b.kills_by_means, b.interruption));
+        }
+        assert_eq!(overall, parser.overall);
+
+        let event: GameEvent = serde_json::from_str(r#"{"timestamp":"0:01","action":{"ClientConnect":{"player_id":2}}}"#).unwrap();
+        assert_eq!(event.action, Action::ClientConnect { player_id: 2 });
+    }
+
+    #[test]
+    fn test_parse_file_integration() {
+        let temp_content = r#"0:00 InitGame: \sv_hostname\Test Server
+0:01 ClientConnect: 1
+0:02 ClientUserinfoChanged: 1 n\TestPlayer\t\0
+0:03 Item: 1 weapon_shotgun
+0:04 Kill: 1 1 2: TestPlayer killed Bot by MOD_SHOTGUN
+0:05 ShutdownGame:"#;
+
+        let temp_dir = std::env::temp_dir();
+        let temp_file_path = temp_dir.join("test_log.txt");
+        std::fs::write(&temp_file_path, temp_content).unwrap();
+
+        let mut parser = LogParser::new();
+        parser.parse_file(&temp_file_path).unwrap();
+
+        std::fs::remove_file(&temp_file_path).unwrap();
+
+        assert_eq!(parser.games.len(), 1);
+        let game = &parser.games[0];
+        assert!(game.completed);
+        assert_eq!(game.events.len(), 6);
+
+        let players = game.get_players();
+        assert_eq!(players.len(), 1);
+        assert_eq!(players.get(&1), Some(&"TestPlayer".to_string()));
+
+        let kills = game.get_kills();
+        assert_eq!(kills.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_kill_with_world() {
+        let event = EventRef::parse("20:54 Kill: 1022 2 22: <world> killed Isgalamido by MOD_TRIGGER_HURT").unwrap().to_event();
+
+        assert_eq!(event.timestamp, "20:54");
+        if let Action::Kill { kill_id, player_id, victim_id, player_name, victim_name, method } = event.action {
+            assert_eq!(kill_id, 1022);
+            assert_eq!(player_id, 2);
+            assert_eq!(victim_id, 22);
+            assert_eq!(player_name, "<world>");
+            assert_eq!(victim_name, "Isgalamido");
+            assert_eq!(method, "MOD_TRIGGER_HURT");
+        } else {
+            panic!("Expected Kill action");
+        }
+    }
+
+    #[test]
+    fn test_parse_other_actions() {
+        let event = EventRef::parse("15:00 Exit: Timelimit hit.").unwrap().to_event();
+
+        assert_eq!(event.timestamp, "15:00");
+        if let Action::Other { action_name, details } = event.action {
+            assert_eq!(action_name, "Exit");
+            assert_eq!(details, "Timelimit hit.");
+        } else {
+            panic!("Expected Other action");
+        }
+    }
+
+    #[test]
+    fn test_empty_and_invalid_lines() {
+
+        assert!(EventRef::parse("").is_none());
+        assert!(EventRef::parse("   ").is_none());
+        assert!(EventRef::parse("invalid line without timestamp").is_none());
+        assert!(EventRef::parse("20:34").is_none());
+    }
+
+    #[test]
+    fn test_kills_by_means_aggregation() {
+        let mut game = Game::new(1);
+
+        // Add some kill events with different methods
+        let kill1 = GameEvent {
+            timestamp: "20:00".to_string(),
+            action: Action::Kill {
+                kill_id: 1,
+                player_id: 2,
+                victim_id: 3,
+                player_name: "Alice".to_string(),
+                victim_name: "Bob".to_string(),
+                method: "MOD_ROCKET_SPLASH".to_string(),
+            }
+        };
+
+        let kill2 = GameEvent {
+            timestamp: "20:01".to_string(),
+            action: Action::Kill {
+                kill_id: 2,
+                player_id: 2,
+                victim_id: 4,
+                player_name: "Alice".to_string(),
+                victim_name: "Charlie".to_string(),
+                method: "MOD_ROCKET_SPLASH".to_string(),
+            }
+        };
+
+        let kill3 = GameEvent {
+            timestamp: "20:02".to_string(),
+            action: Action::Kill {
+                kill_id: 3,
+                player_id: 3,
+                victim_id: 2,
+                player_name: "Bob".to_string(),
+                victim_name: "Alice".to_string(),
+                method: "MOD_SHOTGUN".to_string(),
+            }
+        };
+
+        game.add_event(kill1);
+        game.add_event(kill2);
+        game.add_event(kill3);
+
+        // Test kills by means aggregation
+        assert_eq!(game.kills_by_means.get("MOD_ROCKET_SPLASH"), Some(&2));
+        assert_eq!(game.kills_by_means.get("MOD_SHOTGUN"), Some(&1));
+
+        // Test killers aggregation
+        assert_eq!(game.killers.get("Alice"), Some(&2));
+        assert_eq!(game.killers.get("Bob"), Some(&1));
+        assert_eq!(game.killers.get("<world>"), None); // <world> should not be included
+    }
+
+    #[test]
+    fn test_overall_aggregation() {
+        let mut parser = LogParser::new();
+
+        let events = [
+            "0:00 InitGame: \\sv_hostname\\Test Server",
+            "0:01 Kill: 1 2 3: Alice killed Bob by MOD_ROCKET_SPLASH",
+            "0:02 Kill: 2 2 4: Alice killed Charlie by MOD_SHOTGUN",
+            "0:03 ShutdownGame:",
+            "0:04 InitGame: \\sv_hostname\\Test Server 2",
+            "0:05 Kill: 3 3 2: Bob killed Alice by MOD_ROCKET_SPLASH",
+            "0:06 ShutdownGame:",
+        ];
+
+        parser.parse_reader(events.join("\n").as_bytes()).unwrap();
+
+        // Test overall aggregations
+        assert_eq!(parser.overall.kills_by_means.get("MOD_ROCKET_SPLASH"), Some(&2));
+        assert_eq!(parser.overall.kills_by_means.get("MOD_SHOTGUN"), Some(&1));
+
+        assert_eq!(parser.overall.killers.get("Alice"), Some(&2));
+        assert_eq!(parser.overall.killers.get("Bob"), Some(&1));
+    }
+
+    #[test]
+    fn test_world_kills_excluded() {
+        let mut game = Game::new(1);
+
+        let world_kill = GameEvent {
+            timestamp: "20:00".to_string(),
+            action: Action::Kill {
+                kill_id: 1022,
+                player_id: 2,
+                victim_id: 22,
+                player_name: "<world>".to_string(),
+                victim_name: "Alice".to_string(),
+                method: "MOD_TRIGGER_HURT".to_string(),
+            }
+        };
+
+        game.add_event(world_kill);
+
+        // <world> kills should be counted in kills_by_means but not in killers
+        assert_eq!(game.kills_by_means.get("MOD_TRIGGER_HURT"), Some(&1));
+        assert_eq!(game.killers.get("<world>"), None);
+        assert!(game.killers.is_empty());
+    }
+
+    #[test]
+    fn test_ranking_order() {
+        let mut parser = LogParser::new();
+
+        let events = [
+            "0:00 InitGame: \\sv_hostname\\Test Server",
+            "0:01 Kill: 1 1 2: Charlie killed Alice by MOD_ROCKET_SPLASH", // Charlie: 1 kill
+            "0:02 Kill: 2 2 1: Alice killed Charlie by MOD_SHOTGUN",      // Alice: 1 kill
+            "0:03 Kill: 3 2 3: Alice killed Bob by MOD_RAILGUN",          // Alice: 2 kills total
+            "0:04 Kill: 4 3 2: Bob killed Alice by MOD_MACHINEGUN",       // Bob: 1 kill
+            "0:05 Kill: 5 2 3: Alice killed Bob by MOD_ROCKET_SPLASH",    // Alice: 3 kills total
+            "0:06 ShutdownGame:",
+        ];
+
+        parser.parse_reader(events.join("\n").as_bytes()).unwrap();
+
+        // Test that killers are properly sorted
+        let mut sorted_killers: Vec<_> = parser.overall.killers.iter().collect();
+        sorted_killers.sort_by(|a, b| b.1.cmp(a.1));
+
+        // Alice should be first with 3 kills, then Charlie and Bob tied with 1 kill each
+        assert_eq!(sorted_killers[0], (&"Alice".to_string(), &3));
+        assert!(sorted_killers[1].1 == &1); // Either Charlie or Bob
+        assert!(sorted_killers[2].1 == &1); // Either Charlie or Bob
+        assert_eq!(sorted_killers.len(), 3);
+    }
+
+    #[test]
+    fn test_game_metadata() {
+        let mut parser = LogParser::new();
+
+        let events = [
+            "0:00 InitGame: \\sv_hostname\\Test Server\\g_gametype\\= 4\\mapname\\Q3TOURNEY6_CTF",
+            "0:01 ClientConnect: 1",
+            "12:34 ShutdownGame:",
+        ];
+
+        parser.parse_reader(events.join("\n").as_bytes()).unwrap();
+
+        let game = &parser.games[0];
+        assert_eq!(game.server_var("sv_hostname"), Some("Test Server"));
+        assert_eq!(game.map_name(), Some("Q3TOURNEY6_CTF"));
+        assert_eq!(game.game_type(), Some("Capture The Flag"));
+        assert_eq!(game.duration(), Some(754));
+        assert_eq!(format_duration(754), "12:34");
+    }
+
+    #[test]
+    fn test_scoreboard() {
+        let mut parser = LogParser::new();
+
+        let events = vec![
+            "0:00 InitGame: \\sv_hostname\\Test Server",
+            "0:01 ClientUserinfoChanged: 2 n\\Alice\\t\\0",
+            "0:01 ClientUserinfoChanged: 3 n\\Bob\\t\\0",
+            "0:01 ClientUserinfoChanged: 4 n\\Charlie\\t\\0",
+            "0:02 Kill: 2 3 6: Alice killed Bob by MOD_ROCKET",
+            "0:03 Kill: 1022 2 22: <world> killed Alice by MOD_TRIGGER_HURT",
+            "0:04 Kill: 3 3 7: Bob killed Bob by MOD_ROCKET_SPLASH",
+            "0:05 Kill: 3 2 6: Bob killed Alice by MOD_ROCKET",
+            "0:06 ShutdownGame:",
+        ];
+
+        parser.parse_reader(events.join("\n").as_bytes()).unwrap();
+
+        let scoreboard = parser.games[0].get_scoreboard();
+        let names: Vec<&str> = scoreboard.iter().map(|p| p.name.as_str()).collect();
+        // Tied on score, Bob is ahead on kills: killing himself counts as one
+        assert_eq!(names, vec!["Bob", "Alice", "Charlie"]);
+        let row = |player: &PlayerStats| (player.kills, player.deaths, player.suicides, player.score);
+        assert_eq!(row(&scoreboard[0]), (2, 2, 1, 0));
+        assert_eq!(row(&scoreboard[1]), (1, 2, 1, 0));
+        assert_eq!(scoreboard[2].deaths, 0);
+
+        // Bob's kill came after Alice's, when he was the underdog
+        assert!(scoreboard[0].rating > INITIAL_RATING);
+        assert!(scoreboard[1].rating < INITIAL_RATING);
+        assert!((scoreboard[0].rating + scoreboard[1].rating - 2.0 * INITIAL_RATING).abs() < 1e-9);
+        assert_eq!(scoreboard[2].rating, INITIAL_RATING);
+
+        let matrix = parser.games[0].kill_matrix();
+        assert_eq!(matrix.get(&("<world>".to_string(), "Alice".to_string())), Some(&1));
+        assert_eq!(matrix.get(&("Bob".to_string(), "Bob".to_string())), Some(&1));
+    }
+
+    #[test]
+    fn test_scoreboard_uses_server_scores() {
+        let mut parser = LogParser::new();
+
+        let events = [
+            "0:00 InitGame: \\sv_hostname\\Test Server",
+            "0:01 Kill: 2 2 3: Alice killed Bob by MOD_ROCKET",
+            "0:02 Exit: Fraglimit hit.",
+            "0:02 score: 20  ping: 4  client: 2 Alice",
+            "0:02 score: -3  ping: 15  client: 3 Bob",
+            "0:03 ShutdownGame:",
+        ];
+
+        parser.parse_reader(events.join("\n").as_bytes()).unwrap();
+
+        let game = &parser.games[0];
+        assert_eq!(game.get_scores(), vec![("Alice".to_string(), 20), ("Bob".to_string(), -3)]);
+        assert_eq!(game.winner(), Some("Alice".to_string()));
+
+        // Bob killed no one, so only his totals list him
+        let leaderboard = parser.get_leaderboard();
+        assert_eq!(leaderboard.iter().map(|p| (p.name.as_str(), p.score)).collect::<Vec<_>>(), vec![("Alice", 20)]);
+        assert_eq!(parser.get_overall_stats().players["Bob"].score, -3);
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test Server
+0:01 Kill: 1 2 3: Alice killed Bob by MOD_RAILGUN
+0:02 ShutdownGame:
+0:03 InitGame: \\sv_hostname\\Test Server
+0:04 ClientConnect: 2";
+
+        let mut parser = LogParser::new();
+        parser.parse_reader(log.as_bytes()).unwrap();
+
+        assert_eq!(parser.games.len(), 2);
+        assert!(parser.games[0].completed);
+        assert!(!parser.games[1].completed);
+        assert_eq!(parser.overall.killers.get("Alice"), Some(&1));
+    }
+
+    #[test]
+    fn test_parse_reader_repairs_invalid_utf8() {
+        let log: &[u8] = b"0:00 InitGame: \\sv_hostname\\Test\r\n0:01 ClientUserinfoChanged: 2 n\\J\xf6rg\\t\\0\r\n";
+
+        let mut parser = LogParser::new();
+        parser.parse_reader(log).unwrap();
+
+        assert_eq!(parser.get_repaired_lines(), 1);
+        assert_eq!(parser.games[0].get_players().get(&2).map(String::as_str), Some("Jörg"));
+    }
+
+    /// Adds the one space `Display` writes that a log line may leave out:
+    /// the details of an `Other` event always follow its name after a space,
+    /// so ` 10:12 red:8  blue:6` prints as `10:12 red: 8  blue:6`.
+    fn canonical(line: &str, action: &Action) -> String {
+        let line = line.trim();
+        match action {
+            Action::Other { action_name, details } if !details.is_empty() => {
+                let (timestamp, rest) = line.split_once(' ').unwrap();
+                let details = rest[action_name.len() + 1..].trim_start();
+                format!("{} {}: {}", timestamp, action_name, details)
+            }
+            _ => line.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let log = include_str!("../resources/qgames.log.txt");
+        let mut events = 0;
+        let mut malformed = Vec::new();
+        for (number, line) in log.lines().enumerate() {
+            let event = match EventRef::parse_checked(line) {
+                Ok(Some(event)) => event.to_event(),
+                Ok(None) => {
+                    assert!(line.trim().is_empty() || line.ends_with("---"), "skipped {:?}", line);
+                    continue;
+                }
+                Err(_) => {
+                    malformed.push(number + 1);
+                    continue;
+                }
+            };
+            let printed = event.to_string();
+
+            assert_eq!(printed.trim(), canonical(line, &event.action));
+            assert_eq!(EventRef::parse(&printed).map(|e| e.to_event()), Some(event));
+            events += 1;
+        }
+        // ` 26  0:00 ----`, a separator with a stray column in front
+        assert_eq!(malformed, vec![97]);
+        assert!(events > 5000);
+
+        let event = GameEvent { timestamp: "1:02".to_string(), action: Action::ShutdownGame };
+        assert_eq!(event.to_string(), "  1:02 ShutdownGame:");
+    }
+
+    #[test]
+    fn test_parse_checked_reasons() {
+        assert_eq!(EventRef::parse_checked("  ").unwrap(), None);
+        assert_eq!(EventRef::parse_checked("  0:00 ------------------------------").unwrap(), None);
+        let reason = |line| EventRef::parse_checked(line).map_err(|malformed| (malformed.column, malformed.reason));
+        assert_eq!(reason("Kill: 1 2 3"), Err((1, "expected a `M:SS` timestamp followed by an event")));
+        assert_eq!(reason(" 0:01 ClientConnect:  two"), Err((23, "invalid client id")));
+        assert_eq!(
+            reason("0:02 Kill: 1 2 3: Alice shot Bob"),
+            Err((19, "expected `<killer> killed <victim> by <means>` in Kill"))
+        );
+        assert_eq!(reason("0:03 garbage"), Err((6, "expected an event name followed by `:`")));
+    }
+
+    #[test]
+    fn test_parse_reader_diagnostics() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test
+0:01 ClientConnect: two
+0:02 Kill: 1 2 3: Alice killed Bob by MOD_RAILGUN
+0:03 garbage
+0:04 ShutdownGame:
+";
+        let mut parser = LogParser::new();
+        parser.parse_reader(log.as_bytes()).unwrap();
+
+        assert_eq!(parser.get_malformed_lines(), 2);
+        let diagnostics = parser.get_diagnostics();
+        assert_eq!((diagnostics[0].line, diagnostics[0].text.as_str()), (2, "0:01 ClientConnect: two"));
+        assert_eq!(diagnostics[1].to_string(), "line 4, column 6: expected an event name followed by `:`: 0:03 garbage");
+        assert_eq!(parser.overall.killers.get("Alice"), Some(&1));
+
+        let mut strict = LogParser::new();
+        strict.set_strict(true);
+        let err = strict.parse_reader(log.as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 21: invalid client id: 0:01 ClientConnect: two");
+    }
+
+    const INTERRUPTED_LOG: &str = "\
+0:00 InitGame: \\sv_hostname\\Test\\mapname\\q3dm17
+5:00 Kill: 2 3 7: Alice killed Bob by MOD_ROCKET
+0:00 InitGame: \\sv_hostname\\Test\\mapname\\q3dm17
+1:00 Kill: 3 2 7: Bob killed Alice by MOD_ROCKET
+2:00 InitGame: \\sv_hostname\\Test\\mapname\\q3dm6
+2:30 Kill: 2 3 7: Alice killed Bob by MOD_ROCKET
+3:00 ShutdownGame:
+0:00 InitGame: \\sv_hostname\\Test\\mapname\\q3dm6
+0:10 Kill: 2 3 7: Alice killed Bob by MOD_SHOTGUN
+";
+
+    #[test]
+    fn test_interrupted_games() {
+        let mut parser = LogParser::new();
+        parser.parse_reader(INTERRUPTED_LOG.as_bytes()).unwrap();
+
+        let statuses: Vec<&str> = parser.games.iter().map(Game::status).collect();
+        assert_eq!(statuses, vec!["crash", "map change", "completed", "truncated"]);
+        assert_eq!(
+            parser.overall.interrupted,
+            vec![(1, Interruption::Crash), (2, Interruption::MapChange), (4, Interruption::Truncated)]
+        );
+        // Only the completed game is ranked
+        assert_eq!(parser.overall.killers.get("Alice"), Some(&1));
+        assert_eq!(parser.overall.killers.get("Bob"), None);
+        assert_eq!(parser.overall.kills_by_means.get("MOD_SHOTGUN"), None);
+    }
+
+    #[test]
+    fn test_sample_log_interruptions() {
+        let mut parser = LogParser::new();
+        parser.parse_reader(include_str!("../resources/qgames.log.txt").as_bytes()).unwrap();
+
+        for game in parser.get_games() {
+            assert!(!(game.completed && game.interruption.is_some()), "game {} is {}", game.id, game.status());
+        }
+        let crashed: Vec<u32> = parser.overall.interrupted.iter().map(|(id, _)| *id).collect();
+        assert_eq!(crashed, vec![2]);
+    }
+
+    #[test]
+    fn test_merge_restarts() {
+        let mut parser = LogParser::new();
+        parser.set_merge_restarts(true);
+        parser.parse_reader(INTERRUPTED_LOG.as_bytes()).unwrap();
+
+        let games = parser.get_games();
+        assert_eq!(games.iter().map(|g| g.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(games[0].lines, 1..5);
+        assert_eq!(games[0].get_kills().len(), 2);
+        assert_eq!(games[0].interruption, Some(Interruption::MapChange));
+        // Timed as 5:00 before the restart and 1:00 after it
+        assert_eq!(games[0].duration(), Some(360));
+        assert_eq!(games[0].elapsed_times(), Some(vec![0, 300, 300, 360]));
+        // A new map isn't a restart
+        assert_eq!(games[1].map_name(), Some("q3dm6"));
+        assert!(games[1].completed);
+    }
+
+    #[test]
+    fn test_parse_multiple_files() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test Server
+------------------------------------------------------------
+0:01 Kill: 1 2 3: Alice killed Bob by MOD_RAILGUN
+0:02 ShutdownGame:
+";
+        let paths: Vec<PathBuf> = (1..=2)
+            .map(|n| std::env::temp_dir().join(format!("log_parser_multi_{}_{}.log", n, std::process::id())))
+            .collect();
+        for path in &paths {
+            fs::write(path, log).unwrap();
+        }
+
+        let mut parser = LogParser::new();
+        for path in &paths {
+            parser.parse_file(path).unwrap();
+        }
+        parser.parse_reader(log.as_bytes()).unwrap();
+
+        let games = parser.get_games();
+        assert_eq!(games.iter().map(|g| g.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(games[1].source.as_deref(), Some(paths[1].as_path()));
+        assert_eq!(games[1].lines, 1..5);
+        assert_eq!(games[2].source, None);
+        assert_eq!(parser.overall.killers.get("Alice"), Some(&3));
+
+        for path in &paths {
+            fs::remove_file(path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use crate::report::{capitalize, join_ids, sorted_counts, Ranking, Reporter};
+use crate::{Game, OverallStats};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// The same summary as `TextReporter` as Markdown, with padded tables so the
+/// raw text lines up as well as the rendered output.
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownReporter {
+    pub ranking: Ranking,
+}
+
+impl Reporter for MarkdownReporter {
+    fn write_report(&self, games: &[Game], overall: &OverallStats, out: &mut dyn Write) -> io::Result<()> {
+        write_report(games, overall, &self.ranking, out)
+    }
+}
+
+fn write_report(games: &[Game], overall: &OverallStats, ranking: &Ranking, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "# Match Summary\n\nParsed {} games.", games.len())?;
+
+    for game in games {
+        writeln!(
+            out,
+            "\n## Game {}\n\n{} events ({})\n",
+            game.id,
+            game.events.len(),
+            game.status()
+        )?;
+
+        let players = game.get_players();
+        let mut sorted_players: Vec<_> = players.iter().collect();
+        sorted_players.sort();
+        writeln!(out, "**Players:** {}\n", players.len())?;
+        if !sorted_players.is_empty() {
+            let rows: Vec<Vec<String>> = sorted_players
+                .iter()
+                .map(|(id, name)| vec![id.to_string(), name.to_string()])
+                .collect();
+            write_table(out, &[("Client", true), ("Name", false)], &rows)?;
+        }
+
+        writeln!(out, "**Kills:** {}\n", game.get_kills().len())?;
+
+        if !game.kills_by_means.is_empty() {
+            writeln!(out, "**Kills by means:**\n")?;
+            write_table(out, &[("Means", false), ("Kills", true)], &count_rows(&game.kills_by_means))?;
+        }
+
+        if !game.killers.is_empty() {
+            writeln!(out, "**Killers:**\n")?;
+            write_table(out, &[("Player", false), ("Kills", true)], &count_rows(&game.killers))?;
+        }
+
+        for (name, counts) in game.stats.iter().filter(|(_, counts)| !counts.is_empty()) {
+            writeln!(out, "**{}:**\n", escape(&capitalize(name)))?;
+            write_table(out, &[("Name", false), ("Count", true)], &count_rows(counts))?;
+        }
+    }
+
+    writeln!(out, "\n## Overall Statistics\n")?;
+    write_stats(overall, ranking, out)?;
+
+    if overall.servers.len() > 1 {
+        for (server, stats) in &overall.servers {
+            writeln!(out, "## Server: {}\n", escape(server))?;
+            write_stats(stats, ranking, out)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_stats(overall: &OverallStats, ranking: &Ranking, out: &mut dyn Write) -> io::Result<()> {
+    if !overall.kills_by_means.is_empty() {
+        writeln!(out, "### Kills by means\n")?;
+        write_table(out, &[("Means", false), ("Kills", true)], &count_rows(&overall.kills_by_means))?;
+    }
+
+    for (name, counts) in overall.stats.iter().filter(|(_, counts)| !counts.is_empty()) {
+        writeln!(out, "### {}\n", escape(&capitalize(name)))?;
+        write_table(out, &[("Name", false), ("Count", true)], &count_rows(counts))?;
+    }
+
+    let ranked = ranking.rank_overall(overall);
+    if !ranked.is_empty() {
+        writeln!(out, "### Player Ranking\n")?;
+        let rows: Vec<Vec<String>> = ranked
+            .iter()
+            .enumerate()
+            .map(|(rank, player)| {
+                vec![
+                    (rank + 1).to_string(),
+                    player.name.clone(),
+                    player.kills.to_string(),
+                    player.deaths.to_string(),
+                    format!("{:.2}", player.kd_ratio()),
+                    player.score.to_string(),
+                    format!("{:.0}", player.rating),
+                ]
+            })
+            .collect();
+        let headers = [
+            ("Place", true),
+            ("Player", false),
+            ("Kills", true),
+            ("Deaths", true),
+            ("K/D", true),
+            ("Score", true),
+            ("Rating", true),
+        ];
+        write_table(out, &headers, &rows)?;
+    }
+
+    if !overall.interrupted.is_empty() {
+        writeln!(out, "### Interrupted games\n\nNot counted above.\n")?;
+        let rows: Vec<Vec<String>> = overall
+            .interrupted
+            .iter()
+            .map(|(id, interruption)| vec![id.to_string(), interruption.describe().to_string()])
+            .collect();
+        write_table(out, &[("Game", true), ("Cause", false)], &rows)?;
+    }
+
+    if !overall.excluded.is_empty() {
+        writeln!(out, "Games excluded by the inclusion rules: {}\n", join_ids(&overall.excluded))?;
+    }
+
+    Ok(())
+}
+
+fn count_rows(counts: &HashMap<String, u32>) -> Vec<Vec<String>> {
+    sorted_counts(counts)
+        .into_iter()
+        .map(|(name, count)| vec![name.clone(), count.to_string()])
+        .collect()
+}
+
+/// Writes a table whose columns are padded to their widest cell. The bool in
+/// each header marks a right-aligned (numeric) column.
+fn write_table(out: &mut dyn Write, headers: &[(&str, bool)], rows: &[Vec<String>]) -> io::Result<()> {
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|cell| escape(cell)).collect())
+        .collect();
+
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, (header, _))| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(header.chars().count()))
+                .max()
+                .unwrap_or(0)
+                .max(3)
+        })
+        .collect();
+
+    let pad = |text: &str, width: usize, right: bool| {
+        let fill = " ".repeat(width - text.chars().count());
+        if right {
+            format!("{}{}", fill, text)
+        } else {
+            format!("{}{}", text, fill)
+        }
+    };
+
+    let header_cells: Vec<String> = headers
+        .iter()
+        .zip(&widths)
+        .map(|((header, right), width)| pad(header, *width, *right))
+        .collect();
+    writeln!(out, "| {} |", header_cells.join(" | "))?;
+
+    let separator_cells: Vec<String> = headers
+        .iter()
+        .zip(&widths)
+        .map(|((_, right), width)| {
+            if *right {
+                format!("{}:", "-".repeat(width - 1))
+            } else {
+                "-".repeat(*width)
+            }
+        })
+        .collect();
+    writeln!(out, "| {} |", separator_cells.join(" | "))?;
+
+    for row in &rows {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(headers.iter().zip(&widths))
+            .map(|(cell, ((_, right), width))| pad(cell, *width, *right))
+            .collect();
+        writeln!(out, "| {} |", cells.join(" | "))?;
+    }
+
+    writeln!(out)
+}
+
+/// Escapes characters that would break a table cell or render as markup.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '|' | '\\' | '*' | '_' | '`' | '<' | '>' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogParser;
+
+    #[test]
+    fn test_write_table_alignment() {
+        let mut out = Vec::new();
+        let rows = vec![
+            vec!["MOD_ROCKET_SPLASH".to_string(), "12".to_string()],
+            vec!["MOD_SHOTGUN".to_string(), "3".to_string()],
+        ];
+        write_table(&mut out, &[("Means", false), ("Kills", true)], &rows).unwrap();
+
+        let table = String::from_utf8(out).unwrap();
+        let expected = "\
+| Means               | Kills |
+| ------------------- | ----: |
+| MOD\\_ROCKET\\_SPLASH |    12 |
+| MOD\\_SHOTGUN        |     3 |
+
+";
+        assert_eq!(table, expected);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("<world>"), "\\<world\\>");
+        assert_eq!(escape("a|b"), "a\\|b");
+    }
+
+    #[test]
+    fn test_write_report() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test Server
+0:01 ClientUserinfoChanged: 2 n\\Alice\\t\\0
+0:02 Kill: 2 2 3: Alice killed Bob by MOD_RAILGUN
+0:03 ShutdownGame:
+";
+        let mut parser = LogParser::new();
+        parser.parse_reader(log.as_bytes()).unwrap();
+
+        let mut out = Vec::new();
+        MarkdownReporter::default()
+            .write_report(parser.get_games(), parser.get_overall_stats(), &mut out)
+            .unwrap();
+        let markdown = String::from_utf8(out).unwrap();
+
+        assert!(markdown.contains("## Game 1\n\n4 events (completed)"));
+        assert!(markdown.contains("| Client | Name  |\n| -----: | ----- |\n|      2 | Alice |"));
+        assert!(markdown.contains("### Player Ranking"));
+        assert!(markdown.contains("|     1 | Alice  |     1 |      0 | 1.00 |     1 |   1016 |"));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::input::{self, Compression};
+use crate::{LogParser, ParseError};
+use memmap2::Mmap;
+use std::borrow::Cow;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// A log file mapped into memory, whose lines can be parsed straight from
+/// the mapped bytes without reading the file into a buffer first.
+pub struct MappedLog {
+    mmap: Mmap,
+}
+
+impl MappedLog {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the map is only read. If another process truncates the file
+        // while it is mapped, reading the lost pages raises SIGBUS, the usual
+        // caveat of memory-mapped input; rotated logs are not rewritten.
+        let mmap = unsafe { Mmap::map(&file)? };
+        #[cfg(unix)]
+        mmap.advise(memmap2::Advice::Sequential)?;
+        Ok(MappedLog { mmap })
+    }
+
+    pub fn compression(&self) -> Compression {
+        Compression::detect(&self.mmap)
+    }
+
+    /// The lines of the log, decoded one by one with `input::decode_line`:
+    /// each borrows from the map unless it needed repair.
+    pub fn lines(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        input::lines(&self.mmap).map(input::decode_line)
+    }
+}
+
+impl LogParser {
+    /// Parses a log file through a memory map instead of reading it, on up
+    /// to `threads` threads like `parse_parallel`. Lines are parsed into
+    /// `EventRef`s borrowing from the map, and only lines that aren't valid
+    /// UTF-8 are copied to be repaired; the stored `GameEvent`s own their
+    /// text like those of any other input. Compressed files can't be parsed
+    /// in place and are decompressed into memory instead.
+    pub fn parse_file_mapped<P: AsRef<Path>>(
+        &mut self,
+        file_path: P,
+        threads: usize,
+    ) -> Result<(), ParseError> {
+        let log = MappedLog::open(file_path.as_ref()).map_err(|e| ParseError::io(Some(file_path.as_ref()), None, e))?;
+        if log.compression() != Compression::None {
+            return self.parse_file_parallel(file_path, threads);
+        }
+
+        self.source = Some(file_path.as_ref().to_path_buf());
+        let result = self.parse_bytes_parallel(&log.mmap, threads);
+        self.source = None;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActionRef, EventRef};
+    use std::fs;
+
+    fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("log_parser_mmap_{}_{}.log", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_mapped_matches_parse_file() {
+        let path = temp_file("qgames", include_bytes!("../resources/qgames.log.txt"));
+
+        let mut read = LogParser::new();
+        read.parse_file(&path).unwrap();
+        let mut mapped = LogParser::new();
+        mapped.parse_file_mapped(&path, 1).unwrap();
+
+        assert_eq!(mapped.games.len(), read.games.len());
+        for (a, b) in mapped.games.iter().zip(&read.games) {
+            assert_eq!((a.id, &a.lines, &a.source, &a.events), (b.id, &b.lines, &b.source, &b.events));
+        }
+        assert_eq!(mapped.overall, read.overall);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_mapped_events() {
+        let path = temp_file("events", b"0:00 InitGame: \\sv_hostname\\Test\n0:01 ClientConnect: 2\n");
+        let log = MappedLog::open(&path).unwrap();
+
+        let lines: Vec<_> = log.lines().collect();
+        let events: Vec<EventRef> = lines.iter().filter_map(|line| EventRef::parse(line)).collect();
+        assert_eq!(events[1].action, ActionRef::ClientConnect { player_id: 2 });
+        let ActionRef::InitGame { details } = events[0].action else {
+            panic!("expected InitGame, got {:?}", events[0].action);
+        };
+        assert_eq!(details, "\\sv_hostname\\Test");
+        // Fields point into the mapped file
+        assert!(log.mmap.as_ptr_range().contains(&details.as_ptr()));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_mapped_empty_and_invalid() {
+        let empty = temp_file("empty", b"");
+        let mut parser = LogParser::new();
+        parser.parse_file_mapped(&empty, 1).unwrap();
+        assert!(parser.get_games().is_empty());
+
+        let invalid = temp_file("invalid", b"0:00 InitGame: \\sv_hostname\\\xff\n0:01 ClientConnect: 2\n");
+        let mut parser = LogParser::new();
+        parser.parse_file_mapped(&invalid, 2).unwrap();
+        assert_eq!(parser.get_repaired_lines(), 1);
+        assert_eq!(parser.get_games()[0].server.as_deref(), Some("\u{ff}"));
+
+        // Only the repaired line is copied, the others still borrow the map
+        let log = MappedLog::open(&invalid).unwrap();
+        let lines: Vec<_> = log.lines().collect();
+        assert!(matches!(lines[0], Cow::Owned(_)));
+        assert!(matches!(&lines[1], Cow::Borrowed(line) if log.mmap.as_ptr_range().contains(&line.as_ptr())));
+
+        fs::remove_file(&empty).unwrap();
+        fs::remove_file(&invalid).unwrap();
+    }
+}
@@ -0,0 +1,272 @@
+use crate::{handler, input, ActionRef, EventRef, Game, LogParser, ParseError};
+use std::io::Read;
+use std::path::Path;
+use std::thread;
+
+impl LogParser {
+    /// Parses a whole log on up to `threads` threads.
+    ///
+    /// Games don't depend on each other, so the log is split at `InitGame`
+    /// lines and each part is turned into games on its own thread. The games
+    /// are then numbered, classified and added to the overall statistics in
+    /// log order, giving the same result as `parse_reader`.
+    pub fn parse_parallel(&mut self, log: &str, threads: usize) -> Result<(), ParseError> {
+        self.parse_bytes_parallel(log.as_bytes(), threads)
+    }
+
+    /// Like `parse_parallel` for a log that may not be valid UTF-8. Each line
+    /// is decoded on its own like `parse_reader` does, so only lines that
+    /// need repair are copied.
+    pub(crate) fn parse_bytes_parallel(&mut self, log: &[u8], threads: usize) -> Result<(), ParseError> {
+        let mut chunks = split_at_games(log, threads).into_iter();
+        let first = chunks.next().unwrap_or_default();
+        let chunks: Vec<&[u8]> = chunks.collect();
+        self.line_number = 0;
+
+        let (first_result, parsed) = thread::scope(|scope| {
+            let handles: Vec<_> = chunks
+                .iter()
+                .map(|chunk| {
+                    let mut chunk_parser = LogParser::new();
+                    chunk_parser.source = self.source.clone();
+                    chunk_parser.server_label = self.server_label.clone();
+                    chunk_parser.collectors = self.collectors.clone();
+                    scope.spawn(move || chunk_parser.build_games(chunk))
+                })
+                .collect();
+
+            // The first part may continue a game already in progress, so
+            // it's parsed here as usual while the other parts are built
+            let first_result = input::lines(first).try_for_each(|line| {
+                let line = self.decode_line(line);
+                self.push_line(&line).map(|_| ())
+            });
+
+            let parsed: Vec<(Vec<Game>, LogParser)> =
+                handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+            (first_result, parsed)
+        });
+        first_result?;
+
+        for (games, chunk_parser) in parsed {
+            self.repaired_lines += chunk_parser.repaired_lines;
+            // Only the first diagnostics of each part were kept
+            self.malformed_lines += chunk_parser.malformed_lines - chunk_parser.diagnostics.len();
+            for mut diagnostic in chunk_parser.diagnostics {
+                diagnostic.line += self.line_number;
+                if self.strict {
+                    return Err(ParseError::Malformed(diagnostic));
+                }
+                self.add_diagnostic(diagnostic);
+            }
+
+            // Every game starts with `InitGame`, so it's started like one to
+            // number it and end the game it cuts off
+            for mut game in games {
+                game.lines = game.lines.start + self.line_number..game.lines.end + self.line_number;
+                let new_events = game.events.len();
+                if let Some(ended) = self.start_game(game) {
+                    self.update_overall_stats(&ended);
+                    self.games.push(ended);
+                }
+
+                // Handlers hear of the part's events now, in log order
+                if let Some(current) = &self.current_game {
+                    for event in &current.events[current.events.len() - new_events..] {
+                        handler::notify(&mut self.handlers, event);
+                    }
+                }
+            }
+            self.line_number += chunk_parser.line_number;
+        }
+
+        if let Some(game) = self.finish_game() {
+            self.games.push(game);
+        }
+        Ok(())
+    }
+
+    /// Reads a whole log into memory and parses it with `parse_parallel`,
+    /// repairing lines that aren't valid UTF-8 like `parse_reader`.
+    pub fn parse_reader_parallel<R: Read>(&mut self, mut reader: R, threads: usize) -> Result<(), ParseError> {
+        let mut log = Vec::new();
+        if let Err(e) = reader.read_to_end(&mut log) {
+            // The lines read so far aren't parsed yet, so they're counted here
+            let line = log.iter().filter(|&&b| b == b'\n').count() + 1;
+            return Err(ParseError::io(self.source.as_deref(), Some(line), e));
+        }
+        self.parse_bytes_parallel(&log, threads)
+    }
+
+    /// Reads a possibly compressed log file into memory and parses it with
+    /// `parse_parallel`.
+    pub fn parse_file_parallel<P: AsRef<Path>>(
+        &mut self,
+        file_path: P,
+        threads: usize,
+    ) -> Result<(), ParseError> {
+        let reader = crate::open_file(file_path.as_ref())?;
+        self.source = Some(file_path.as_ref().to_path_buf());
+        let result = self.parse_reader_parallel(reader, threads);
+        self.source = None;
+        result
+    }
+
+    /// Builds the games of one part of a log, returning them with the parser
+    /// that read the part, which holds its line count and diagnostics.
+    fn build_games(mut self, chunk: &[u8]) -> (Vec<Game>, LogParser) {
+        let mut games = Vec::new();
+        for line in input::lines(chunk) {
+            self.line_number += 1;
+            let line = self.decode_line(line);
+            // Chunk parsers are never strict, so this can't fail
+            if let Ok(Some(event)) = self.check_line(&line) {
+                games.extend(self.build_game(event));
+            }
+        }
+        games.extend(self.current_game.take());
+        (games, self)
+    }
+}
+
+/// Splits a log into at most `parts` pieces of similar size. Every piece but
+/// the first starts with an `InitGame` line.
+fn split_at_games(log: &[u8], parts: usize) -> Vec<&[u8]> {
+    let target = log.len() / parts.max(1) + 1;
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < log.len() {
+        let mut end = start + target;
+        if end >= log.len() {
+            chunks.push(&log[start..]);
+            break;
+        }
+
+        // Move to the start of the next `InitGame` line
+        end = match log[end..].iter().position(|&b| b == b'\n') {
+            Some(newline) => end + newline + 1,
+            None => log.len(),
+        };
+        while end < log.len() && !is_init_game(next_line(&log[end..])) {
+            end += next_line(&log[end..]).len();
+        }
+
+        chunks.push(&log[start..end]);
+        start = end;
+    }
+
+    chunks
+}
+
+/// The first line of `text`, including its newline.
+fn next_line(text: &[u8]) -> &[u8] {
+    match text.iter().position(|&b| b == b'\n') {
+        Some(newline) => &text[..=newline],
+        None => text,
+    }
+}
+
+fn is_init_game(line: &[u8]) -> bool {
+    EventRef::parse(&input::decode_line(line)).is_some_and(|event| matches!(event.action, ActionRef::InitGame { .. }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interruption;
+
+    const LOG: &str = include_str!("../resources/qgames.log.txt");
+
+    #[test]
+    fn test_split_at_games() {
+        let chunks = split_at_games(LOG.as_bytes(), 8);
+
+        assert!(chunks.len() > 1 && chunks.len() <= 8);
+        assert_eq!(chunks.concat(), LOG.as_bytes());
+        for chunk in &chunks[1..] {
+            assert!(is_init_game(next_line(chunk)));
+        }
+
+        assert_eq!(split_at_games(b"", 4), Vec::<&[u8]>::new());
+        assert_eq!(split_at_games(b"0:00 ClientConnect: 2\n", 4), vec![b"0:00 ClientConnect: 2\n"]);
+
+        let names = "0:00 InitGame: \\sv_hostname\\Café\n".repeat(20);
+        assert_eq!(split_at_games(names.as_bytes(), 7).concat(), names.as_bytes());
+    }
+
+    #[test]
+    fn test_parse_parallel_matches_sequential() {
+        let mut sequential = LogParser::new();
+        sequential.parse_reader(LOG.as_bytes()).unwrap();
+
+        let mut merged = LogParser::new();
+        merged.set_merge_restarts(true);
+        merged.parse_reader(LOG.as_bytes()).unwrap();
+
+        for threads in [1, 3, 16] {
+            for expected in [&sequential, &merged] {
+                let mut parallel = LogParser::new();
+                parallel.set_merge_restarts(expected.merge_restarts);
+                parallel.parse_parallel(LOG, threads).unwrap();
+
+                assert_eq!(parallel.games.len(), expected.games.len());
+                for (a, b) in parallel.games.iter().zip(&expected.games) {
+                    assert_eq!((a.id, &a.lines, &a.events, a.interruption), (b.id, &b.lines, &b.events, b.interruption));
+                }
+                assert_eq!(parallel.overall, expected.overall);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_parallel_diagnostics() {
+        let log = "0:00 InitGame: a\n0:01 oops\n0:02 InitGame: b\n0:03 ClientConnect: x\n";
+        let mut parser = LogParser::new();
+        parser.parse_parallel(log, 2).unwrap();
+
+        let lines: Vec<usize> = parser.get_diagnostics().iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![2, 4]);
+        assert_eq!(parser.get_malformed_lines(), 2);
+
+        let mut strict = LogParser::new();
+        strict.set_strict(true);
+        let err = strict.parse_parallel("0:00 InitGame: a\n0:02 InitGame: b\n0:03 oops\n", 2).unwrap_err();
+        assert!(err.to_string().starts_with("line 3, column 6: "));
+    }
+
+    #[test]
+    fn test_parse_parallel_merges_restarts_across_parts() {
+        // The crash and its restart are built together on another thread
+        let first_game = "0:00 InitGame: \\mapname\\q3dm1\n0:01 ShutdownGame:\n".repeat(5);
+        let log = first_game
+            + "\
+0:00 InitGame: \\mapname\\q3dm17
+5:00 Kill: 2 3 7: Alice killed Bob by MOD_ROCKET
+0:00 InitGame: \\mapname\\q3dm17
+1:00 ShutdownGame:
+0:00 InitGame: \\mapname\\q3dm6
+";
+        assert_eq!(split_at_games(log.as_bytes(), 2).len(), 2);
+        let mut parser = LogParser::new();
+        parser.set_merge_restarts(true);
+        parser.parse_parallel(&log, 2).unwrap();
+
+        let games = parser.get_games();
+        assert_eq!(games.len(), 7);
+        assert_eq!((games[5].status(), games[5].lines.clone()), ("completed", 11..15));
+        assert_eq!(games[6].interruption, Some(Interruption::Truncated));
+        assert_eq!(parser.overall.killers.get("Alice"), Some(&1));
+    }
+
+    #[test]
+    fn test_parse_parallel_repairs_lines() {
+        let log = b"0:00 InitGame: \\sv_hostname\\Caf\xe9\n0:01 ClientConnect: 2\n0:02 InitGame: \\sv_hostname\\Bar\n";
+        let mut parser = LogParser::new();
+        parser.parse_reader_parallel(&log[..], 2).unwrap();
+
+        assert_eq!(parser.get_repaired_lines(), 1);
+        assert_eq!(parser.games[0].server.as_deref(), Some("Café"));
+        assert_eq!(parser.games[1].lines, 3..4);
+    }
+}
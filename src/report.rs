@@ -0,0 +1,359 @@
+use crate::{Game, OverallStats, PlayerStats};
+use clap::ValueEnum;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Renders parse results in some output format.
+///
+/// Implementations receive every parsed game together with the statistics
+/// aggregated over all of them, and write the whole report to `out`.
+pub trait Reporter {
+    fn write_report(&self, games: &[Game], overall: &OverallStats, out: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Statistic a player ranking is sorted by, highest first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SortKey {
+    #[default]
+    Kills,
+    Deaths,
+    /// Kills per death
+    Kd,
+    Score,
+    Rating,
+}
+
+impl SortKey {
+    fn compare(&self, a: &PlayerStats, b: &PlayerStats) -> Ordering {
+        match self {
+            SortKey::Kills => a.kills.cmp(&b.kills),
+            SortKey::Deaths => a.deaths.cmp(&b.deaths),
+            SortKey::Kd => a.kd_ratio().total_cmp(&b.kd_ratio()),
+            SortKey::Score => a.score.cmp(&b.score),
+            SortKey::Rating => a.rating.total_cmp(&b.rating),
+        }
+    }
+
+    /// Describes the player's value for this key, e.g. `12 kills`.
+    pub fn describe(&self, player: &PlayerStats) -> String {
+        match self {
+            SortKey::Kills => format!("{} kills", player.kills),
+            SortKey::Deaths => format!("{} deaths", player.deaths),
+            SortKey::Kd => format!("K/D {:.2}", player.kd_ratio()),
+            SortKey::Score => format!("score {}", player.score),
+            SortKey::Rating => format!("rating {:.0}", player.rating),
+        }
+    }
+}
+
+/// How player rankings are ordered and truncated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ranking {
+    pub sort_by: SortKey,
+    /// Keep only the first N players
+    pub top: Option<usize>,
+}
+
+impl Ranking {
+    /// Sorts players by the ranking key, highest first. Ties are broken by
+    /// kills, then fewest deaths, then name, so the order never depends on
+    /// hash map iteration.
+    pub fn rank(&self, mut players: Vec<PlayerStats>) -> Vec<PlayerStats> {
+        players.sort_by(|a, b| {
+            self.sort_by
+                .compare(b, a)
+                .then(b.kills.cmp(&a.kills))
+                .then(a.deaths.cmp(&b.deaths))
+                .then(a.name.cmp(&b.name))
+        });
+        if let Some(top) = self.top {
+            players.truncate(top);
+        }
+        players
+    }
+
+    /// Ranks the players of `overall.leaderboard`, who are the players of
+    /// the killers list with the same kills whatever the ranking key.
+    pub fn rank_overall(&self, overall: &OverallStats) -> Vec<PlayerStats> {
+        self.rank(overall.leaderboard())
+    }
+}
+
+/// Sorts a count map by count descending, then by name.
+pub(crate) fn sorted_counts(counts: &HashMap<String, u32>) -> Vec<(&String, &u32)> {
+    let mut sorted: Vec<_> = counts.iter().collect();
+    sorted.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    sorted
+}
+
+/// The plain text summary printed by default.
+#[derive(Debug, Clone, Default)]
+pub struct TextReporter {
+    pub ranking: Ranking,
+}
+
+impl TextReporter {
+    /// Writes the section for a single game.
+    pub fn write_game(&self, game: &Game, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "\nGame {}: {} events ({})", game.id, game.events.len(), game.status())?;
+
+        let players = game.get_players();
+        let mut sorted_players: Vec<_> = players.iter().collect();
+        sorted_players.sort();
+        writeln!(out, "  Players: {}", players.len())?;
+        for (id, name) in sorted_players {
+            writeln!(out, "    {}: {}", id, name)?;
+        }
+
+        let kills = game.get_kills();
+        writeln!(out, "  Kills: {}", kills.len())?;
+
+        // Show kills by means for this game
+        if !game.kills_by_means.is_empty() {
+            writeln!(out, "  Kills by means:")?;
+            for (method, count) in sorted_counts(&game.kills_by_means) {
+                writeln!(out, "    {}: {}", method, count)?;
+            }
+        }
+
+        // Show killers for this game
+        if !game.killers.is_empty() {
+            writeln!(out, "  Killers:")?;
+            for (killer, count) in sorted_counts(&game.killers) {
+                writeln!(out, "    {}: {} kills", killer, count)?;
+            }
+        }
+
+        // Show the counts of any extra collectors
+        for (name, counts) in game.stats.iter().filter(|(_, counts)| !counts.is_empty()) {
+            writeln!(out, "  {}:", capitalize(name))?;
+            for (key, count) in sorted_counts(counts) {
+                writeln!(out, "    {}: {}", key, count)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the overall statistics and the player ranking, followed by the
+    /// same for each server when games came from more than one.
+    pub fn write_overall(&self, overall: &OverallStats, out: &mut dyn Write) -> io::Result<()> {
+        // Show overall statistics
+        writeln!(out, "\n=== Overall Statistics ===")?;
+        self.write_stats(overall, out)?;
+
+        if overall.servers.len() > 1 {
+            for (server, stats) in &overall.servers {
+                writeln!(out, "\n=== Server: {} ===", server)?;
+                self.write_stats(stats, out)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_stats(&self, overall: &OverallStats, out: &mut dyn Write) -> io::Result<()> {
+        // Overall kills by means
+        if !overall.kills_by_means.is_empty() {
+            writeln!(out, "\nOverall kills by means:")?;
+            for (method, count) in sorted_counts(&overall.kills_by_means) {
+                writeln!(out, "  {}: {}", method, count)?;
+            }
+        }
+
+        // Overall killers
+        if !overall.killers.is_empty() {
+            writeln!(out, "\nOverall killers (top players by kills):")?;
+            let killers = sorted_counts(&overall.killers);
+            for (killer, count) in killers.iter().take(self.ranking.top.unwrap_or(killers.len())) {
+                writeln!(out, "  {}: {} kills", killer, count)?;
+            }
+        }
+
+        for (name, counts) in overall.stats.iter().filter(|(_, counts)| !counts.is_empty()) {
+            writeln!(out, "\nOverall {}:", name)?;
+            for (key, count) in sorted_counts(counts) {
+                writeln!(out, "  {}: {}", key, count)?;
+            }
+        }
+
+        // Player Ranking Report
+        let ranked = self.ranking.rank_overall(overall);
+        if !ranked.is_empty() {
+            writeln!(out, "\n=== PLAYER RANKING REPORT ===")?;
+
+            for (rank, player) in ranked.iter().enumerate() {
+                let position = match rank + 1 {
+                    1 => "1st".to_string(),
+                    2 => "2nd".to_string(),
+                    3 => "3rd".to_string(),
+                    n => format!("{}th", n),
+                };
+                writeln!(out, "{:>4} place: {} with {}", position, player.name, self.ranking.sort_by.describe(player))?;
+            }
+        }
+
+        // Games cut off without `ShutdownGame`
+        if !overall.interrupted.is_empty() {
+            writeln!(out, "\nInterrupted games (not counted above):")?;
+            for (id, interruption) in &overall.interrupted {
+                writeln!(out, "  Game {}: {}", id, interruption.describe())?;
+            }
+        }
+
+        if !overall.excluded.is_empty() {
+            writeln!(out, "\nGames excluded by the inclusion rules: {}", join_ids(&overall.excluded))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Reporter for TextReporter {
+    fn write_report(&self, games: &[Game], overall: &OverallStats, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "Parsed {} games:", games.len())?;
+
+        for game in games {
+            self.write_game(game, out)?;
+        }
+
+        self.write_overall(overall, out)
+    }
+}
+
+/// Upper-cases the first letter of a collector name for a heading.
+pub(crate) fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Formats game ids as a comma-separated list.
+pub fn join_ids(ids: &[u32]) -> String {
+    ids.iter().map(u32::to_string).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogParser;
+
+    #[test]
+    fn test_text_reporter() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test Server
+0:01 Kill: 2 2 3: Alice killed Bob by MOD_RAILGUN
+0:02 ShutdownGame:
+";
+        let mut parser = LogParser::new();
+        parser.parse_reader(log.as_bytes()).unwrap();
+
+        let mut out = Vec::new();
+        TextReporter::default()
+            .write_report(parser.get_games(), parser.get_overall_stats(), &mut out)
+            .unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.starts_with("Parsed 1 games:\n\nGame 1: 3 events (completed)\n"));
+        assert!(text.contains("\nOverall kills by means:\n  MOD_RAILGUN: 1\n"));
+        assert!(text.contains(" 1st place: Alice with 1 kills\n"));
+    }
+
+    #[test]
+    fn test_text_reporter_servers() {
+        let mut parser = LogParser::new();
+        for (label, killer) in [("eu", "Alice"), ("us", "Bob")] {
+            parser.set_server_label(Some(label.to_string()));
+            let log = format!(
+                "0:00 InitGame: \\sv_hostname\\Test Server\n0:01 Kill: 2 3 3: {} killed Carol by MOD_RAILGUN\n0:02 ShutdownGame:\n",
+                killer
+            );
+            parser.parse_reader(log.as_bytes()).unwrap();
+        }
+
+        let mut out = Vec::new();
+        TextReporter::default()
+            .write_overall(parser.get_overall_stats(), &mut out)
+            .unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.contains("\n=== Overall Statistics ===\n\nOverall kills by means:\n  MOD_RAILGUN: 2\n"));
+        assert!(text.contains("\n=== Server: eu ===\n\nOverall kills by means:\n  MOD_RAILGUN: 1\n"));
+        let us = text.split("\n=== Server: us ===\n").nth(1).unwrap();
+        assert!(us.contains(" 1st place: Bob with 1 kills\n"));
+    }
+
+    #[test]
+    fn test_kills_ranking_matches_killers() {
+        let mut parser = LogParser::new();
+        parser.parse_reader(include_str!("../resources/qgames.log.txt").as_bytes()).unwrap();
+        let overall = parser.get_overall_stats();
+
+        let ranked = Ranking::default().rank_overall(overall);
+        assert_eq!(ranked.len(), overall.killers.len());
+        for player in &ranked {
+            assert_eq!(player.kills, overall.killers[&player.name]);
+        }
+        // The same players with the same statistics, whatever the key
+        for sort_by in SortKey::value_variants() {
+            let mut players = Ranking { sort_by: *sort_by, top: None }.rank_overall(overall);
+            players.sort_by(|a, b| a.name.cmp(&b.name));
+            let mut by_kills = ranked.clone();
+            by_kills.sort_by(|a, b| a.name.cmp(&b.name));
+            assert_eq!(players, by_kills, "ranked by {:?}", sort_by);
+
+            let isgalamido = players.iter().find(|p| p.name == "Isgalamido").unwrap();
+            assert_eq!((isgalamido.kills, format!("{:.2}", isgalamido.kd_ratio())), (184, "1.29".to_string()));
+        }
+
+        let mut out = Vec::new();
+        let reporter = TextReporter { ranking: Ranking { top: Some(2), ..Default::default() } };
+        reporter.write_overall(overall, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("top players by kills):\n  Isgalamido: 184 kills\n  Zeh: 158 kills\n\n"));
+        assert!(text.contains(" 1st place: Isgalamido with 184 kills\n 2nd place: Zeh with 158 kills\n\n"));
+    }
+
+    fn player(name: &str, kills: u32, deaths: u32, score: i32, rating: f64) -> PlayerStats {
+        PlayerStats { name: name.to_string(), kills, deaths, suicides: 0, score, rating }
+    }
+
+    #[test]
+    fn test_ranking_sort_keys() {
+        let players = vec![
+            player("Alice", 10, 5, 8, 1010.0),
+            player("Bob", 6, 1, 6, 1040.0),
+            player("Charlie", 10, 8, 9, 990.0),
+        ];
+        let names = |sort_by| -> Vec<String> {
+            Ranking { sort_by, top: None }
+                .rank(players.clone())
+                .into_iter()
+                .map(|p| p.name)
+                .collect()
+        };
+
+        assert_eq!(names(SortKey::Kills), vec!["Alice", "Charlie", "Bob"]);
+        assert_eq!(names(SortKey::Deaths), vec!["Charlie", "Alice", "Bob"]);
+        assert_eq!(names(SortKey::Kd), vec!["Bob", "Alice", "Charlie"]);
+        assert_eq!(names(SortKey::Score), vec!["Charlie", "Alice", "Bob"]);
+        assert_eq!(names(SortKey::Rating), vec!["Bob", "Alice", "Charlie"]);
+    }
+
+    #[test]
+    fn test_ranking_ties_and_top() {
+        let players = vec![
+            player("Mal", 3, 2, 0, 1000.0),
+            player("Zeh", 3, 2, 0, 1000.0),
+            player("Dono", 3, 2, 0, 1000.0),
+            player("Isgalamido", 3, 1, 0, 1000.0),
+        ];
+        let ranked = Ranking { sort_by: SortKey::Kills, top: Some(3) }.rank(players);
+        let names: Vec<&str> = ranked.iter().map(|p| p.name.as_str()).collect();
+
+        assert_eq!(names, vec!["Isgalamido", "Dono", "Mal"]);
+    }
+}
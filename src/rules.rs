@@ -0,0 +1,102 @@
+use crate::Game;
+
+/// Which games count towards the overall statistics and rankings. Games
+/// that fail a rule are still parsed and reported on their own.
+///
+/// The default admits every game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InclusionRules {
+    /// Fewest players a game must have had
+    pub min_players: usize,
+    /// Shortest game to count, in seconds
+    pub min_duration: u32,
+    /// Only count games that reached `Exit` (a time, frag or capture limit)
+    pub require_exit: bool,
+    /// Leave out games where every player was a bot
+    pub exclude_bot_only: bool,
+}
+
+impl InclusionRules {
+    pub fn admits(&self, game: &Game) -> bool {
+        let players = game.get_players();
+        players.len() >= self.min_players
+            && game.duration().unwrap_or(0) >= self.min_duration
+            && (!self.require_exit || game.reached_exit())
+            && !(self.exclude_bot_only && !players.is_empty() && game.get_bots().len() == players.len())
+    }
+}
+
+/// Parses a minimum duration given as seconds or as `M:SS`, where `SS` is
+/// two digits under 60.
+pub fn parse_duration(value: &str) -> Result<u32, String> {
+    let digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    let seconds = match value.split_once(':') {
+        None if digits(value) => value.parse().ok(),
+        Some((minutes, seconds)) if digits(minutes) && seconds.len() == 2 && digits(seconds) && seconds < "60" => {
+            let seconds: u32 = seconds.parse().unwrap();
+            minutes.parse::<u32>().ok().and_then(|m| m.checked_mul(60)?.checked_add(seconds))
+        }
+        _ => return Err(format!("expected seconds or M:SS, got `{}`", value)),
+    };
+    seconds.ok_or_else(|| format!("duration `{}` is too long", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogParser;
+
+    const LOG: &str = "\
+0:00 InitGame: \\sv_hostname\\Test
+0:01 ClientUserinfoChanged: 2 n\\Alice\\t\\0\\model\\sarge
+0:01 ClientUserinfoChanged: 3 n\\Bob\\t\\0\\model\\sarge
+2:00 Kill: 2 3 7: Alice killed Bob by MOD_ROCKET
+2:00 Exit: Fraglimit hit.
+2:05 ShutdownGame:
+0:00 InitGame: \\sv_hostname\\Test
+0:01 ClientUserinfoChanged: 2 n\\Sarge\\t\\0\\model\\sarge\\skill\\5.000000
+0:01 ClientUserinfoChanged: 3 n\\Daemia\\t\\0\\model\\daemia\\skill\\5.000000
+0:30 Kill: 2 3 7: Sarge killed Daemia by MOD_ROCKET
+0:35 ShutdownGame:
+";
+
+    fn admitted(rules: InclusionRules) -> Vec<bool> {
+        let mut parser = LogParser::new();
+        parser.parse_reader(LOG.as_bytes()).unwrap();
+        parser.get_games().iter().map(|game| rules.admits(game)).collect()
+    }
+
+    #[test]
+    fn test_inclusion_rules() {
+        assert_eq!(admitted(InclusionRules::default()), vec![true, true]);
+        assert_eq!(admitted(InclusionRules { min_players: 3, ..Default::default() }), vec![false, false]);
+        assert_eq!(admitted(InclusionRules { min_duration: 60, ..Default::default() }), vec![true, false]);
+        assert_eq!(admitted(InclusionRules { require_exit: true, ..Default::default() }), vec![true, false]);
+        assert_eq!(admitted(InclusionRules { exclude_bot_only: true, ..Default::default() }), vec![true, false]);
+    }
+
+    #[test]
+    fn test_excluded_games_leave_rankings() {
+        let mut parser = LogParser::new();
+        parser.set_inclusion_rules(InclusionRules { exclude_bot_only: true, ..Default::default() });
+        parser.parse_reader(LOG.as_bytes()).unwrap();
+
+        let overall = parser.get_overall_stats();
+        assert_eq!(overall.excluded, vec![2]);
+        assert_eq!(overall.killers.get("Alice"), Some(&1));
+        assert_eq!(overall.killers.get("Sarge"), None);
+        assert_eq!(overall.servers["Test"].excluded, vec![2]);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Ok(90));
+        assert_eq!(parse_duration("1:30"), Ok(90));
+        assert_eq!(parse_duration("0:05"), Ok(5));
+        for invalid in ["soon", "1:75", "1:60", "1:5", "1:050", ":30", "1:", "-5", "1:3a"] {
+            assert_eq!(parse_duration(invalid), Err(format!("expected seconds or M:SS, got `{}`", invalid)));
+        }
+        assert_eq!(parse_duration("80000000:00"), Err("duration `80000000:00` is too long".to_string()));
+        assert_eq!(parse_duration("99999999999"), Err("duration `99999999999` is too long".to_string()));
+    }
+}
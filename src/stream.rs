@@ -0,0 +1,178 @@
+use crate::{Game, LogParser, ParseError};
+use std::io::BufRead;
+use std::mem;
+use std::path::PathBuf;
+
+/// Iterator over the games of a log, parsed one line at a time.
+///
+/// Created by `LogParser::stream`. Each game is yielded once its
+/// `ShutdownGame` (or the next `InitGame`) is read, and the last game is
+/// yielded when the input ends.
+pub struct GameStream<'a, R> {
+    parser: &'a mut LogParser,
+    reader: R,
+    line: Vec<u8>,
+    done: bool,
+    /// The file given to `stream_file`, lent to the parser only while
+    /// `next` runs, so the parser never keeps it once the stream is dropped
+    pub(crate) source: Option<PathBuf>,
+}
+
+impl<'a, R: BufRead> GameStream<'a, R> {
+    pub(crate) fn new(parser: &'a mut LogParser, reader: R) -> Self {
+        GameStream {
+            parser,
+            reader,
+            line: Vec::new(),
+            done: false,
+            source: None,
+        }
+    }
+
+    /// Reads up to the end of the next game, with `source` lent to the parser.
+    fn next_game(&mut self) -> Option<Result<Game, ParseError>> {
+        loop {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Ok(0) => {
+                    self.done = true;
+                    return self.parser.finish_game().map(Ok);
+                }
+                Ok(_) => {
+                    let line = self.parser.decode_line(&self.line);
+                    match self.parser.consume_line(&line) {
+                        Ok(Some(game)) => return Some(Ok(game)),
+                        Ok(None) => {}
+                        Err(e) => {
+                            self.done = true;
+                            return Some(Err(e));
+                        }
+                    }
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(self.parser.io_error(e)));
+                }
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for GameStream<'_, R> {
+    type Item = Result<Game, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        mem::swap(&mut self.parser.source, &mut self.source);
+        let next = self.next_game();
+        mem::swap(&mut self.parser.source, &mut self.source);
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interruption;
+    use std::io::{self, Cursor};
+
+    #[test]
+    fn test_stream_yields_games_as_they_end() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test Server
+0:01 Kill: 1 2 3: Alice killed Bob by MOD_RAILGUN
+0:02 ShutdownGame:
+0:03 InitGame: \\sv_hostname\\Test Server
+0:04 Kill: 1 3 2: Bob killed Alice by MOD_RAILGUN
+0:05 InitGame: \\sv_hostname\\Test Server
+0:06 Kill: 1 3 2: Bob killed Alice by MOD_SHOTGUN
+";
+        let mut parser = LogParser::new();
+        let mut stream = parser.stream(Cursor::new(log));
+
+        let first = stream.next().unwrap().unwrap();
+        assert_eq!(first.id, 1);
+        assert!(first.completed);
+
+        let second = stream.next().unwrap().unwrap();
+        assert_eq!(second.id, 2);
+        assert!(!second.completed);
+
+        let third = stream.next().unwrap().unwrap();
+        assert_eq!(third.id, 3);
+        assert_eq!(third.events.len(), 2);
+
+        assert!(stream.next().is_none());
+        assert!(stream.next().is_none());
+
+        // Streamed games are not retained, but still count towards the totals
+        // unless they were cut off
+        assert!(parser.get_games().is_empty());
+        let overall = parser.get_overall_stats();
+        assert_eq!(overall.kills_by_means.get("MOD_RAILGUN"), Some(&1));
+        assert_eq!(overall.killers.get("Alice"), Some(&1));
+        assert_eq!(overall.killers.get("Bob"), None);
+        assert_eq!(overall.interrupted, vec![(2, Interruption::MapChange), (3, Interruption::Truncated)]);
+    }
+
+    #[test]
+    fn test_consume_line_keeps_no_games() {
+        let log = "0:00 InitGame: \\sv_hostname\\Test\n0:01 ShutdownGame:\n";
+        let mut parser = LogParser::new();
+        let mut games = Vec::new();
+        for _ in 0..2 {
+            for line in log.lines() {
+                games.extend(parser.consume_line(line).unwrap());
+            }
+            // As when a followed log is rotated
+            parser.reset_line_number();
+        }
+
+        assert!(parser.get_games().is_empty());
+        assert_eq!(games.iter().map(|game| (game.id, game.lines.clone())).collect::<Vec<_>>(), vec![(1, 1..3), (2, 1..3)]);
+    }
+
+    #[test]
+    fn test_stream_file_dropped_early() {
+        let path = std::env::temp_dir().join(format!("log_parser_stream_{}.log", std::process::id()));
+        std::fs::write(&path, "0:00 InitGame: \\sv_hostname\\A\n0:01 ShutdownGame:\n0:00 InitGame: \\sv_hostname\\A\n").unwrap();
+        let mut parser = LogParser::new();
+        let first = parser.stream_file(&path).unwrap().next().unwrap().unwrap();
+        assert_eq!(first.source.as_deref(), Some(path.as_path()));
+
+        parser.parse_reader("0:00 InitGame: \\sv_hostname\\B\n".as_bytes()).unwrap();
+        assert_eq!(parser.get_games().last().unwrap().source, None);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    struct FailingReader;
+
+    impl io::Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk on fire"))
+        }
+    }
+
+    #[test]
+    fn test_stream_reports_read_errors() {
+        let mut parser = LogParser::new();
+        let mut stream = parser.stream(io::BufReader::new(FailingReader));
+
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn test_stream_repairs_invalid_utf8() {
+        let mut parser = LogParser::new();
+        let invalid_utf8: &[u8] = b"0:00 InitGame: \\sv_hostname\\\xff\n";
+        let game = parser.stream(invalid_utf8).next().unwrap().unwrap();
+
+        assert_eq!(game.server.as_deref(), Some("\u{ff}"));
+        assert_eq!(parser.get_repaired_lines(), 1);
+    }
+}
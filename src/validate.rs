@@ -0,0 +1,185 @@
+use crate::{parse_timestamp, Action, Game};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, Write};
+
+/// An impossible sequence of events found in a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    /// Timestamp of the event where the problem shows
+    pub timestamp: String,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>6} {}", self.timestamp, self.message)
+    }
+}
+
+/// Replays the events of a game, tracking which clients are connected and
+/// under which name, and returns every event that doesn't fit.
+pub fn validate(game: &Game) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut connected = HashSet::new();
+    let mut disconnected = HashSet::new();
+    let mut names: HashMap<u32, &str> = HashMap::new();
+    let mut previous_time = None;
+
+    for event in &game.events {
+        let mut issue = |message: String| {
+            issues.push(Issue {
+                timestamp: event.timestamp.clone(),
+                message,
+            })
+        };
+
+        let time = parse_timestamp(&event.timestamp);
+        match &event.action {
+            // A restart merged into the game starts a new clock
+            Action::InitGame { .. } => {}
+            _ => {
+                if let (Some(time), Some(previous)) = (time, previous_time) {
+                    if time < previous {
+                        issue("timestamp goes backwards".to_string());
+                    }
+                }
+            }
+        }
+        previous_time = time.or(previous_time);
+
+        match &event.action {
+            Action::ClientConnect { player_id } => {
+                connected.insert(*player_id);
+                disconnected.remove(player_id);
+            }
+            Action::ClientUserinfoChanged { player_id, info } => {
+                if let Some(name) = crate::extract_player_name(info) {
+                    names.insert(*player_id, name);
+                }
+            }
+            Action::ClientBegin { player_id } if !connected.contains(player_id) => {
+                issue(format!("client {} begins before connecting", player_id));
+            }
+            Action::ClientDisconnect { player_id } => {
+                if disconnected.contains(player_id) {
+                    issue(format!("client {} disconnects twice", player_id));
+                } else if !connected.contains(player_id) {
+                    issue(format!("client {} disconnects without connecting", player_id));
+                }
+                connected.remove(player_id);
+                disconnected.insert(*player_id);
+                names.remove(player_id);
+            }
+            // `Kill: <killer> <victim> <means>:` is parsed into `kill_id`,
+            // `player_id` and `victim_id` in that order
+            Action::Kill { kill_id: killer_id, player_id: victim_id, player_name, victim_name, .. } => {
+                let mut clients = vec![(*victim_id, victim_name)];
+                if player_name != "<world>" {
+                    clients.insert(0, (*killer_id, player_name));
+                }
+                for (id, name) in clients {
+                    if !connected.contains(&id) {
+                        issue(format!("kill involves client {} ({}), which isn't connected", id, name));
+                    } else if names.get(&id).is_some_and(|current| current != name) {
+                        issue(format!("kill names client {} {}, but its name is {}", id, name, names[&id]));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    issues
+}
+
+/// Writes the issues of each game and returns how many games had any.
+pub fn write_report(games: &[Game], out: &mut dyn Write) -> io::Result<usize> {
+    let mut invalid = 0;
+    for game in games {
+        let issues = validate(game);
+        write!(out, "Game {}", game.id)?;
+        if let Some(path) = &game.source {
+            write!(out, " ({}:{})", path.display(), game.lines.start)?;
+        }
+        if issues.is_empty() {
+            writeln!(out, ": ok")?;
+            continue;
+        }
+
+        invalid += 1;
+        writeln!(out, ": {} issues", issues.len())?;
+        for issue in issues {
+            writeln!(out, "  {}", issue)?;
+        }
+    }
+    Ok(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogParser;
+
+    fn issues(log: &str) -> Vec<String> {
+        let mut parser = LogParser::new();
+        parser.parse_reader(log.as_bytes()).unwrap();
+        validate(&parser.get_games()[0]).iter().map(Issue::to_string).collect()
+    }
+
+    #[test]
+    fn test_validate_consistent_game() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test
+0:01 ClientConnect: 2
+0:01 ClientUserinfoChanged: 2 n\\Alice\\t\\0
+0:01 ClientBegin: 2
+0:02 ClientConnect: 3
+0:02 ClientUserinfoChanged: 3 n\\Bob\\t\\0
+0:03 Kill: 2 3 7: Alice killed Bob by MOD_ROCKET
+0:04 Kill: 1022 2 22: <world> killed Alice by MOD_TRIGGER_HURT
+0:05 ClientDisconnect: 3
+0:06 ShutdownGame:
+";
+        assert_eq!(issues(log), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_validate_impossible_sequences() {
+        let log = "\
+0:00 InitGame: \\sv_hostname\\Test
+0:01 ClientBegin: 2
+0:01 ClientConnect: 2
+0:01 ClientUserinfoChanged: 2 n\\Alice\\t\\0
+0:03 Kill: 2 4 7: Alice killed Carol by MOD_ROCKET
+0:02 Kill: 2 2 7: Alicia killed Alice by MOD_ROCKET
+0:05 ClientDisconnect: 2
+0:06 ClientDisconnect: 2
+";
+        assert_eq!(
+            issues(log),
+            vec![
+                "  0:01 client 2 begins before connecting",
+                "  0:03 kill involves client 4 (Carol), which isn't connected",
+                "  0:02 timestamp goes backwards",
+                "  0:02 kill names client 2 Alicia, but its name is Alice",
+                "  0:06 client 2 disconnects twice",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_report() {
+        let mut parser = LogParser::new();
+        parser
+            .parse_reader(&b"0:00 InitGame: a\n0:01 ClientBegin: 2\n0:02 InitGame: b\n"[..])
+            .unwrap();
+
+        let mut out = Vec::new();
+        assert_eq!(write_report(parser.get_games(), &mut out).unwrap(), 1);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Game 1: 1 issues\n    0:01 client 2 begins before connecting\nGame 2: ok\n"
+        );
+    }
+}